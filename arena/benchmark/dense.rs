@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
 use pui_arena::base::dense::Arena;
 
@@ -83,6 +83,28 @@ pub fn dense(c: &mut Criterion) {
 
         b.iter(|| black_box(&arena).iter().sum::<i32>())
     });
+    c.bench_function("dense extend exact", |b| {
+        b.iter_batched(
+            || (0..1_000_000).collect::<Vec<i32>>(),
+            |values| {
+                let mut arena = Arena::new();
+                arena.extend(values);
+                arena
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("dense drain", |b| {
+        b.iter_batched(
+            || {
+                let mut arena = Arena::new();
+                arena.extend((0..1_000_000).map(|i| vec![i]));
+                arena
+            },
+            |mut arena| arena.drain().for_each(drop),
+            BatchSize::LargeInput,
+        )
+    });
 }
 
 criterion_group!(benches, dense);
@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
 use pui_arena::base::hop::Arena;
 
@@ -48,6 +48,38 @@ pub fn hop(c: &mut Criterion) {
             let _ = black_box((a, b, c, d, e));
         });
     });
+    c.bench_function("hop removal", |b| {
+        b.iter_batched(
+            || {
+                let mut arena = Arena::new();
+                let keys: Vec<pui_arena::Key<usize>> = (0..1_000_000).map(|i| arena.insert(i)).collect();
+                (arena, keys)
+            },
+            |(mut arena, keys)| {
+                for key in keys {
+                    black_box(arena.remove(key));
+                }
+                arena
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("hop retain middle half", |b| {
+        b.iter_batched(
+            || {
+                let mut arena = Arena::new();
+                for i in 0..1_000_000 {
+                    let _: usize = arena.insert(i);
+                }
+                arena
+            },
+            |mut arena| {
+                arena.retain(|&mut i| !(250_000..750_000).contains(&i));
+                arena
+            },
+            BatchSize::LargeInput,
+        )
+    });
     c.bench_function("hop iteration non-contigious", |b| {
         let mut arena = Arena::new();
 
@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 
 use pui_arena::base::sparse::Arena;
 
@@ -48,6 +48,44 @@ pub fn sparse(c: &mut Criterion) {
             let _ = black_box((a, b, c, d, e));
         });
     });
+    c.bench_function("sparse removal", |b| {
+        b.iter_batched(
+            || {
+                let mut arena = Arena::new();
+                let keys: Vec<pui_arena::Key<usize>> = (0..1_000_000).map(|i| arena.insert(i)).collect();
+                (arena, keys)
+            },
+            |(mut arena, keys)| {
+                for key in keys {
+                    black_box(arena.remove(key));
+                }
+                arena
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("sparse branded contains", |b| {
+        use pui_arena::{pui_core::dynamic::Dynamic, pui_vec::Id, Key};
+
+        let mut arena = Arena::<_, _>::with_ident(Dynamic::create());
+        let keys: Vec<Key<Id<_>>> = (0..1000).map(|i| arena.insert(i)).collect();
+
+        b.iter(|| keys.iter().filter(|key| black_box(&arena).contains(*key)).count())
+    });
+    c.bench_function("sparse usize get", |b| {
+        let mut arena = Arena::new();
+
+        for i in 0..1000 {
+            let _: usize = arena.insert(i);
+        }
+
+        b.iter(|| (0..1000).filter_map(|i: usize| black_box(&arena).get(i)).sum::<i32>())
+    });
+    c.bench_function("vec option get", |b| {
+        let vec = (0..1000).map(Some).collect::<Vec<Option<i32>>>();
+
+        b.iter(|| (0..1000).filter_map(|i| black_box(&vec).get(i).and_then(Option::as_ref)).sum::<i32>())
+    });
     c.bench_function("sparse iteration non-contigious", |b| {
         let mut arena = Arena::new();
 
@@ -26,6 +26,7 @@ impl<Id, V> Key<Id, V> {
 }
 
 impl<'a> Validator<'a> {
+    #[inline]
     pub(crate) fn new() -> Self { Self(PhantomData) }
 
     /// complete this index validator with an unchecked index
@@ -33,17 +34,36 @@ impl<'a> Validator<'a> {
     /// # Safety
     ///
     /// See `ArenaKey::validate_ident`
+    #[inline]
     #[allow(unused_variables)]
     pub unsafe fn unchecked_index<I>(self, ident: &'a I) -> CompleteValidator<'a> { CompleteValidator(true, self) }
 
     /// complete this index validator with an index
+    #[inline]
     pub fn checked_index(self) -> CompleteValidator<'a> { CompleteValidator(false, self) }
 }
 
 impl CompleteValidator<'_> {
+    #[inline]
     pub(crate) fn into_inner(self) -> bool { self.0 }
 }
 
+/// Why a key is or isn't associated with a value in an arena,
+/// returned from `Arena::classify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyStatus {
+    /// The key is associated with a value
+    Live,
+    /// The key was created for an arena with a different identifier
+    WrongIdent,
+    /// The key's index is out of bounds
+    OutOfBounds,
+    /// The key's slot doesn't hold a value
+    Vacant,
+    /// The key's slot holds a value, but not the one this key was created for
+    StaleVersion,
+}
+
 /// A trait to access elements of an `Arena`
 pub trait ArenaKey<I, V: Version> {
     /// An optimization that allows you to construct an unchecked index into the `Arena`
@@ -51,11 +71,18 @@ pub trait ArenaKey<I, V: Version> {
     /// It is only safe to call [`Validator::unchecked_index`]
     /// if the next call to `Self::index` is guarnteed to be in bounds
     /// for the arena with the identifier `ident`
+    #[inline]
     #[allow(unused_variables)]
     fn validate_ident<'a>(&self, ident: &'a I, validator: Validator<'a>) -> CompleteValidator<'a> {
         validator.checked_index()
     }
 
+    /// Check if this key may have been created for the arena with the identifier `ident`
+    ///
+    /// This is only used to classify keys, see [`KeyStatus::WrongIdent`]
+    #[allow(unused_variables)]
+    fn is_owned_by(&self, ident: &I) -> bool { true }
+
     /// The index of this key
     fn index(&self) -> usize;
 
@@ -79,14 +106,40 @@ impl<K: ?Sized + ArenaKey<I, V>, I, V: Version> ArenaKey<I, V> for &K {
         K::validate_ident(self, ident, validator)
     }
 
+    fn is_owned_by(&self, ident: &I) -> bool { K::is_owned_by(self, ident) }
+
     fn index(&self) -> usize { K::index(self) }
 
     fn version(&self) -> Option<V::Save> { K::version(self) }
 }
 
+/// `None` is never associated with a value, so accessing an arena with `None` always yields absence
+impl<K: ArenaKey<I, V>, I, V: Version> ArenaKey<I, V> for Option<K> {
+    fn validate_ident<'a>(&self, ident: &'a I, validator: Validator<'a>) -> CompleteValidator<'a> {
+        match self {
+            Some(key) => key.validate_ident(ident, validator),
+            None => validator.checked_index(),
+        }
+    }
+
+    fn is_owned_by(&self, ident: &I) -> bool {
+        match self {
+            Some(key) => key.is_owned_by(ident),
+            None => true,
+        }
+    }
+
+    // no arena can hold `usize::MAX` slots, so `None` is always out of bounds
+    fn index(&self) -> usize { self.as_ref().map_or(usize::MAX, K::index) }
+
+    fn version(&self) -> Option<V::Save> { self.as_ref().and_then(K::version) }
+}
+
 impl<I, V: Version> ArenaKey<I, V> for usize {
+    #[inline]
     fn index(&self) -> usize { *self }
 
+    #[inline]
     fn version(&self) -> Option<V::Save> { None }
 }
 
@@ -116,6 +169,8 @@ impl<I: pui_core::OneShotIdentifier, V: Version> ArenaKey<I, V> for pui_vec::Id<
         }
     }
 
+    fn is_owned_by(&self, ident: &I) -> bool { ident.owns_token(self.token()) }
+
     fn index(&self) -> usize { self.get() }
 
     fn version(&self) -> Option<V::Save> { None }
@@ -152,6 +207,8 @@ impl<I: pui_core::OneShotIdentifier, V: Version> ArenaKey<I, V> for Key<pui_vec:
         }
     }
 
+    fn is_owned_by(&self, ident: &I) -> bool { ident.owns_token(self.id.token()) }
+
     fn index(&self) -> usize { self.id.get() }
 
     fn version(&self) -> Option<V::Save> { Some(self.version) }
@@ -178,3 +235,82 @@ impl<I, V: Version> ArenaKey<I, V> for Key<crate::TrustedIndex, V::Save> {
 
     fn version(&self) -> Option<V::Save> { Some(self.version) }
 }
+
+/// Implement [`ArenaKey`] and [`BuildArenaKey`] for a newtype around another key,
+/// by forwarding to the given field
+///
+/// ```rust
+/// use pui_arena::{base::sparse::Arena, Key};
+///
+/// #[repr(transparent)]
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// struct NodeId(Key<usize>);
+///
+/// pui_arena::impl_arena_key!(NodeId => .0: Key<usize>);
+///
+/// let mut arena = Arena::new();
+/// let node: NodeId = arena.insert(10);
+/// assert_eq!(arena[node], 10);
+/// assert_eq!(arena.remove(node), 10);
+/// assert!(!arena.contains(node));
+/// ```
+///
+/// Generic newtypes can list their generic parameters after `impl`
+///
+/// ```rust
+/// use pui_arena::{base::sparse::Arena, version::TinyVersion, Key};
+///
+/// #[repr(transparent)]
+/// struct NodeId<V>(Key<usize, V>);
+///
+/// pui_arena::impl_arena_key!(impl[V] NodeId<V> => .0: Key<usize, V>);
+///
+/// let mut arena = Arena::<_, (), TinyVersion>::INIT;
+/// let node: NodeId<_> = arena.insert(10);
+/// assert_eq!(arena[node], 10);
+/// ```
+///
+/// This fails to compile if the newtype has any other fields, because it
+/// must be able to build the newtype from just the inner key.
+#[macro_export]
+macro_rules! impl_arena_key {
+    ($name:ty => .$field:tt: $inner:ty) => {
+        $crate::impl_arena_key!(@impl [] $name => .$field: $inner);
+    };
+    (impl[$($generics:tt)+] $name:ty => .$field:tt: $inner:ty) => {
+        $crate::impl_arena_key!(@impl [$($generics)+,] $name => .$field: $inner);
+    };
+    (@impl [$($generics:tt)*] $name:ty => .$field:tt: $inner:ty) => {
+        impl<$($generics)* __I, __V: $crate::version::Version> $crate::ArenaKey<__I, __V> for $name
+        where
+            $inner: $crate::ArenaKey<__I, __V>,
+        {
+            fn validate_ident<'a>(
+                &self,
+                ident: &'a __I,
+                validator: $crate::Validator<'a>,
+            ) -> $crate::CompleteValidator<'a> {
+                <$inner as $crate::ArenaKey<__I, __V>>::validate_ident(&self.$field, ident, validator)
+            }
+
+            fn is_owned_by(&self, ident: &__I) -> bool {
+                <$inner as $crate::ArenaKey<__I, __V>>::is_owned_by(&self.$field, ident)
+            }
+
+            fn index(&self) -> usize { <$inner as $crate::ArenaKey<__I, __V>>::index(&self.$field) }
+
+            fn version(&self) -> Option<__V::Save> { <$inner as $crate::ArenaKey<__I, __V>>::version(&self.$field) }
+        }
+
+        impl<$($generics)* __I, __V: $crate::version::Version> $crate::BuildArenaKey<__I, __V> for $name
+        where
+            $inner: $crate::BuildArenaKey<__I, __V>,
+        {
+            unsafe fn new_unchecked(index: usize, save: __V::Save, ident: &__I) -> Self {
+                Self {
+                    $field: <$inner as $crate::BuildArenaKey<__I, __V>>::new_unchecked(index, save, ident),
+                }
+            }
+        }
+    };
+}
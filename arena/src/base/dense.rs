@@ -12,6 +12,11 @@
 //!
 //! Each slot is versioned by using [`Version`] trait. See [`Version`] for docs
 //! on version exhaustion. Once a slot's version exhausts, it will not be reused.
+//!
+//! Removing a value normally moves the last value into it's place, which changes
+//! the iteration order. If values must not move, use [`Arena::remove_stable`],
+//! which leaves a hole behind instead. Holes are skipped while iterating, and
+//! can be squeezed out later with [`Arena::compact`].
 
 use core::{
     marker::PhantomData,
@@ -19,11 +24,11 @@ use core::{
     ops::{Index, IndexMut},
 };
 
-use std::{boxed::Box, vec::Vec};
+use std::{boxed::Box, collections::TryReserveError, vec::Vec};
 
 use crate::{
-    base::sparse::{Arena as SparseArena, VacantEntry as SparseVacantEntry},
-    version::{DefaultVersion, Version},
+    base::sparse::{Arena as SparseArena, FreeSlots, VacantEntry as SparseVacantEntry},
+    version::{DefaultVersion, DynVersion, ExhaustionPolicy, Tagged, Version},
     ArenaKey, BuildArenaKey,
 };
 
@@ -113,6 +118,23 @@ impl<T> BoxVec<T> {
         }
     }
 
+    pub fn try_reserve(&mut self, len: usize, additional: usize) -> Result<(), TryReserveError> {
+        let cap = self.inner.len();
+        let ptr = self.inner.as_mut_ptr();
+
+        // if `try_reserve` fails, `vec` still owns the original allocation, so `self.inner` stays valid
+        let mut vec = ManuallyDrop::new(unsafe { Vec::from_raw_parts(ptr, len, cap) });
+        vec.try_reserve(additional)?;
+        unsafe {
+            let bx = Box::from_raw(core::ptr::slice_from_raw_parts_mut(
+                vec.as_mut_ptr().cast(),
+                vec.capacity(),
+            ));
+            core::ptr::write(&mut self.inner, bx);
+        }
+        Ok(())
+    }
+
     pub fn clone(&self, len: usize) -> Self
     where
         T: Clone,
@@ -123,11 +145,15 @@ impl<T> BoxVec<T> {
     }
 }
 
+/// marks a hole left by [`Arena::remove_stable`] in `Arena::keys`
+const HOLE: usize = usize::MAX;
+
 /// A dense arena
 pub struct Arena<T, I = (), V: Version = DefaultVersion> {
     slots: SparseArena<usize, I, V>,
     keys: BoxVec<usize>,
     values: BoxVec<T>,
+    holes: usize,
 }
 
 /// An empty slot in a dense arena
@@ -139,14 +165,11 @@ pub struct VacantEntry<'a, T, K, V: Version = DefaultVersion> {
 }
 
 impl<T, I, V: Version> Drop for Arena<T, I, V> {
-    fn drop(&mut self) {
-        let len = self.slots.len();
-        unsafe { core::ptr::drop_in_place(&mut self.values[Init(..len)]) }
-    }
+    fn drop(&mut self) { unsafe { self.drop_values(self.end(), self.holes) } }
 }
 
-impl<T> Default for Arena<T> {
-    fn default() -> Self { Self::new() }
+impl<T, I: Default, V: Version> Default for Arena<T, I, V> {
+    fn default() -> Self { Self::with_ident(I::default()) }
 }
 
 impl<T> Arena<T> {
@@ -156,19 +179,87 @@ impl<T> Arena<T> {
 
 impl<T: Clone, I: Clone, V: Version> Clone for Arena<T, I, V> {
     fn clone(&self) -> Self {
+        let end = self.end();
         let slots = self.slots.clone();
-        let keys = self.keys.clone(self.slots.len());
-        let values = self.values.clone(self.slots.len());
-        Self { slots, keys, values }
+        let keys = self.keys.clone(end);
+
+        let values = if self.holes == 0 {
+            self.values.clone(end)
+        } else {
+            let mut values = unsafe { BoxVec::new() };
+            values.reserve_exact(0, end);
+
+            for i in 0..end {
+                if self.keys[Init(i)] != HOLE {
+                    values[Uninit(i)] = MaybeUninit::new(self.values[Init(i)].clone());
+                }
+            }
+
+            values
+        };
+
+        Self {
+            slots,
+            keys,
+            values,
+            holes: self.holes,
+        }
+    }
+
+    /// Clones `source` into this arena, reusing it's allocations and the allocations of it's values
+    ///
+    /// If cloning a value panics, this arena is left empty, and it's values are leaked
+    fn clone_from(&mut self, source: &Self) {
+        let is_live = |keys: &BoxVec<usize>, holes: usize, i: usize| holes == 0 || keys[Init(i)] != HOLE;
+
+        let old_end = self.end();
+        let old_holes = self.holes;
+        let new_end = source.end();
+        let common = old_end.min(new_end);
+
+        // forget all values, so they are leaked instead of dropped twice if cloning panics
+        self.slots.delete_all();
+        self.holes = 0;
+
+        for i in 0..common {
+            match (is_live(&self.keys, old_holes, i), is_live(&source.keys, source.holes, i)) {
+                (true, true) => self.values[Init(i)].clone_from(&source.values[Init(i)]),
+                (true, false) => unsafe { core::ptr::drop_in_place(&mut self.values[Init(i)]) },
+                (false, true) => self.values[Uninit(i)] = MaybeUninit::new(source.values[Init(i)].clone()),
+                (false, false) => (),
+            }
+        }
+
+        for i in common..old_end {
+            if is_live(&self.keys, old_holes, i) {
+                unsafe { core::ptr::drop_in_place(&mut self.values[Init(i)]) }
+            }
+        }
+
+        self.values.reserve_exact(common, new_end - common);
+
+        for i in common..new_end {
+            if is_live(&source.keys, source.holes, i) {
+                self.values[Uninit(i)] = MaybeUninit::new(source.values[Init(i)].clone());
+            }
+        }
+
+        self.keys.reserve_exact(0, new_end);
+        self.keys[Uninit(..new_end)].copy_from_slice(&source.keys[Uninit(..new_end)]);
+
+        self.slots.clone_from(&source.slots);
+        self.holes = source.holes;
     }
 }
 
 impl<T, V: Version> Arena<T, (), V> {
     /// Clear the arena without reducing it's capacity
     pub fn clear(&mut self) {
-        let len = self.slots.len();
+        let end = self.end();
+        let holes = self.holes;
         self.slots.clear();
-        unsafe { core::ptr::drop_in_place(&mut self.values[Init(..len)]) }
+        self.holes = 0;
+        unsafe { self.drop_values(end, holes) }
     }
 }
 
@@ -180,6 +271,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
                 slots: SparseArena::with_ident(ident),
                 values: BoxVec::new(),
                 keys: BoxVec::new(),
+                holes: 0,
             }
         }
     }
@@ -193,9 +285,86 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Returns the number of elements in this arena
     pub fn len(&self) -> usize { self.slots.len() }
 
+    /// Returns the number of slots that have exhausted their versions
+    ///
+    /// These slots will never be reused, see [`Version`] for details.
+    pub fn exhausted_slots(&self) -> usize { self.slots.exhausted_slots() }
+
+    /// An iterator over the indices of the vacant slots that can be reused
+    ///
+    /// The indices are yielded in the order of the internal list of empty slots,
+    /// which is the order that they would be filled by `insert`. Exhausted slots
+    /// are never yielded.
+    pub fn free_slots(&self) -> FreeSlots<'_, usize, V> { self.slots.free_slots() }
+
+    /// Returns what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn on_exhaustion(&self) -> ExhaustionPolicy { self.slots.on_exhaustion() }
+
+    /// Set what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn set_on_exhaustion(&mut self, policy: ExhaustionPolicy) { self.slots.set_on_exhaustion(policy) }
+
+    /// Returns an object safe view of the version of the slot at `index`,
+    /// or `None` if `index` is out of bounds
+    ///
+    /// This is intended for tools that inspect arenas, see [`DynVersion`] for details
+    pub fn dyn_version(&self, index: usize) -> Option<Box<dyn DynVersion>>
+    where
+        V: 'static,
+        V::Save: 'static,
+    {
+        self.slots.dyn_version(index)
+    }
+
+    /// Check if the slot at `index` was reused since it had the version `old`
+    ///
+    /// Unlike [`Arena::contains`], this is also true if the slot is currently vacant,
+    /// but the value with the version `old` was removed. It's false if `old`
+    /// is the version the next value inserted in the slot will get.
+    /// If `index` is out of bounds, this returns true.
+    ///
+    /// This compares versions for equality, so it may return false if the slot's
+    /// version started over at `old` after a `drain_reset` or `restore_structure`.
+    pub fn is_reused(&self, index: usize, old: V::Save) -> bool { self.slots.is_reused(index, old) }
+
+    /// Overwrite the version of an occupied slot, this allows testing version exhaustion
+    /// without inserting and removing values from a slot until it's exhausted
+    ///
+    /// # Panics
+    ///
+    /// If the slot at `index` isn't occupied
+    #[cfg(feature = "test-util")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    pub fn force_version(&mut self, index: usize, save: V::Save)
+    where
+        V: crate::version::RestorableVersion,
+    {
+        self.slots.force_version(index, save)
+    }
+
     /// Returns the capacity of this arena
     pub fn capacity(&self) -> usize { self.values.capacity().min(self.keys.capacity()) }
 
+    /// The number of values and holes in `self.values`
+    fn end(&self) -> usize { self.slots.len() + self.holes }
+
+    /// Drop all values in `..end`, skipping over holes
+    ///
+    /// # Safety
+    ///
+    /// `end` and `holes` must describe the values, and the values
+    /// must not be used after this call
+    unsafe fn drop_values(&mut self, end: usize, holes: usize) {
+        if holes == 0 {
+            core::ptr::drop_in_place(&mut self.values[Init(..end)])
+        } else {
+            for i in 0..end {
+                if self.keys[Init(i)] != HOLE {
+                    core::ptr::drop_in_place(&mut self.values[Init(i)])
+                }
+            }
+        }
+    }
+
     /// Reserves the minimum capacity for exactly additional more elements
     /// to be inserted in the given collection. After calling reserve_exact,
     /// capacity will be greater than or equal to `self.len() + additional`.
@@ -205,6 +374,10 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// requests. Therefore, capacity can not be relied upon to be precisely
     /// minimal. Prefer reserve if future insertions are expected.
     pub fn reserve(&mut self, additional: usize) {
+        if additional == 0 {
+            return
+        }
+
         struct Abort;
 
         impl Drop for Abort {
@@ -213,7 +386,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
 
         let abort_on_panic = Abort;
 
-        let len = self.slots.len();
+        let len = self.end();
         self.values.reserve(len, additional);
         self.keys.reserve(len, additional);
 
@@ -231,6 +404,10 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// requests. Therefore, capacity can not be relied upon to be precisely
     /// minimal. Prefer reserve if future insertions are expected.
     pub fn reserve_exact(&mut self, additional: usize) {
+        if additional == 0 {
+            return
+        }
+
         struct Abort;
 
         impl Drop for Abort {
@@ -239,7 +416,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
 
         let abort_on_panic = Abort;
 
-        let len = self.slots.len();
+        let len = self.end();
         self.values.reserve_exact(len, additional);
         self.keys.reserve_exact(len, additional);
 
@@ -248,6 +425,20 @@ impl<T, I, V: Version> Arena<T, I, V> {
         self.slots.reserve_exact(additional);
     }
 
+    /// Tries to reserve capacity for at least additional more elements to be inserted
+    /// in the given collection, without panicking or aborting if the allocation fails.
+    ///
+    /// If this returns an error, the arena's values, keys and `capacity` are unchanged,
+    /// although some of the backing buffers may have grown.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let len = self.end();
+        // `capacity` only depends on `values` and `keys`, so reserving `values`
+        // last ensures that `capacity` only grows if every reservation succeeds
+        self.slots.try_reserve(additional)?;
+        self.keys.try_reserve(len, additional)?;
+        self.values.try_reserve(len, additional)
+    }
+
     #[cold]
     #[inline(never)]
     fn reserve_cold(&mut self, additional: usize) { self.reserve(additional) }
@@ -259,11 +450,33 @@ impl<'a, T, I, V: Version> VacantEntry<'a, T, I, V> {
     pub fn key<K: BuildArenaKey<I, V>>(&self) -> K { self.slots.key() }
 
     /// Insert an element into the vacant entry
-    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K {
+    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K { self.insert_mut(value).0 }
+
+    /// Insert an element into the vacant entry, and return a unique reference to it
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(self, value: T) -> (K, &'a mut T) {
         *self.value = MaybeUninit::new(value);
         let key: K = self.slots.insert(self.index);
         *self.keys = MaybeUninit::new(key.index());
-        key
+        (key, unsafe { &mut *self.value.as_mut_ptr() })
+    }
+}
+
+impl<T, I, V: Version, const BITS: u32> VacantEntry<'_, T, I, Tagged<V, BITS>> {
+    /// Insert an element into the vacant entry with the given tag
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(mut self, tag: u32, value: T) -> K {
+        self.slots.set_tag(tag);
+        self.insert(value)
+    }
+}
+
+impl<T, I, V: Version, const BITS: u32> Arena<T, I, Tagged<V, BITS>> {
+    /// Insert a value in the arena with the given tag, returning key assigned to the value.
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(&mut self, tag: u32, value: T) -> K {
+        self.vacant_entry().insert_tagged(tag, value)
     }
 }
 
@@ -279,7 +492,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// key. The returned VacantEntry reserves a slot in the arena and is able
     /// to query the associated key.
     pub fn vacant_entry(&mut self) -> VacantEntry<'_, T, I, V> {
-        let len = self.len();
+        let len = self.end();
 
         if len == self.capacity() {
             self.reserve_cold(1);
@@ -300,9 +513,66 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// if needed.
     pub fn insert<K: BuildArenaKey<I, V>>(&mut self, value: T) -> K { self.vacant_entry().insert(value) }
 
+    /// Insert a value in the arena, returning key assigned to the value
+    /// and a unique reference to the inserted value.
+    ///
+    /// This avoids looking up the value again after inserting it.
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(&mut self, value: T) -> (K, &mut T) {
+        self.vacant_entry().insert_mut(value)
+    }
+
+    /// Insert all of the values in the arena, returning the keys assigned to them
+    ///
+    /// The values are moved to the end of the arena all at once, so this is faster
+    /// than inserting them one at a time. The keys are in the same order as the values.
+    ///
+    /// ```rust
+    /// # use pui_arena::{base::dense::Arena, Key};
+    /// let mut arena = Arena::new();
+    /// let keys: Vec<Key<usize>> = arena.insert_slice(vec![1, 2, 3]);
+    /// assert_eq!(arena[keys[1]], 2);
+    /// assert_eq!(arena.values(), Some(&[1, 2, 3][..]));
+    /// ```
+    pub fn insert_slice<K: BuildArenaKey<I, V>>(&mut self, mut values: Vec<T>) -> Vec<K> {
+        let start = self.end();
+        let len = values.len();
+        self.reserve(len);
+
+        // the values are owned by the arena after this, but until they are inserted into
+        // `slots` they aren't reachable, so if inserting panics they are leaked
+        unsafe {
+            values.set_len(0);
+            core::ptr::copy_nonoverlapping(
+                values.as_ptr(),
+                self.values[Uninit(start..start + len)].as_mut_ptr().cast(),
+                len,
+            );
+        }
+
+        (start..start + len)
+            .map(|index| {
+                let key: K = self.slots.insert(index);
+                self.keys[Uninit(index)] = MaybeUninit::new(key.index());
+                key
+            })
+            .collect()
+    }
+
     /// Return true if a value is associated with the given key.
     pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool { self.slots.contains(key) }
 
+    /// Return whether each of the given keys is associated with a value
+    ///
+    /// The results are in the same order as the keys
+    pub fn contains_all<K: ArenaKey<I, V>>(&self, keys: &[K]) -> Vec<bool> {
+        keys.iter().map(|key| self.contains(key)).collect()
+    }
+
+    /// Classify the given key, explaining why it is or isn't associated with a value
+    ///
+    /// This returns [`KeyStatus::Live`](crate::KeyStatus::Live) if and only if `contains` would return true
+    pub fn classify<K: ArenaKey<I, V>>(&self, key: K) -> crate::KeyStatus { self.slots.classify(key) }
+
     /// Remove and return the value associated with the given key.
     ///
     /// The key is then released and may be associated with future stored values,
@@ -326,8 +596,22 @@ impl<T, I, V: Version> Arena<T, I, V> {
         Some(self.remove_unchecked(index))
     }
 
+    /// Remove and return the value associated with the given key, along with
+    /// the saved version that the next value inserted into it's slot will have
+    ///
+    /// Keys for this slot with an older version are stale. The version is `None`
+    /// if the slot's version is exhausted, so it will never be reused.
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn remove_versioned<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<(T, Option<V::Save>)> {
+        let (index, version) = self.slots.remove_versioned(key)?;
+        Some((self.remove_unchecked(index), version))
+    }
+
     fn remove_unchecked(&mut self, index: usize) -> T {
-        if index == self.slots.len() {
+        let last = self.end();
+
+        if index == last {
             unsafe { return self.values[Uninit(index)].as_ptr().read() }
         }
 
@@ -335,7 +619,6 @@ impl<T, I, V: Version> Arena<T, I, V> {
 
         unsafe {
             // remove element from vec
-            let last = self.slots.len();
             let ptr = self.values.inner.as_mut_ptr().cast::<T>();
             value = ptr.add(index).read();
             ptr.add(index).copy_from_nonoverlapping(ptr.add(last), 1);
@@ -345,7 +628,9 @@ impl<T, I, V: Version> Arena<T, I, V> {
             let back_ref = *ptr.add(last).cast::<usize>();
             ptr.add(index).copy_from_nonoverlapping(ptr.add(last), 1);
 
-            *self.slots.get_unchecked_mut(back_ref) = index;
+            if back_ref != HOLE {
+                *self.slots.get_unchecked_mut(back_ref) = index;
+            }
         }
 
         value
@@ -383,7 +668,10 @@ impl<T, I, V: Version> Arena<T, I, V> {
                     let ptr = keys.as_mut_ptr();
                     let back_ref = *ptr.add(last).cast::<usize>();
                     ptr.add(index).copy_from_nonoverlapping(ptr.add(last), 1);
-                    *slots.get_unchecked_mut(back_ref) = index;
+
+                    if back_ref != HOLE {
+                        *slots.get_unchecked_mut(back_ref) = index;
+                    }
                 }
             }
         }
@@ -395,7 +683,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
 
         unsafe {
             // remove element from vec
-            let last = self.slots.len();
+            let last = self.end();
             let ptr = self.values.inner.as_mut_ptr().cast::<T>();
 
             let _fixup = if index == last {
@@ -416,6 +704,69 @@ impl<T, I, V: Version> Arena<T, I, V> {
         }
     }
 
+    /// Remove and return the value associated with the given key,
+    /// without moving any other values.
+    ///
+    /// This leaves a hole in the values, which is skipped while iterating,
+    /// until [`compact`](Arena::compact) is called.
+    ///
+    /// Panics if key is not associated with a value.
+    #[track_caller]
+    pub fn remove_stable<K: ArenaKey<I, V>>(&mut self, key: K) -> T {
+        self.try_remove_stable(key)
+            .expect("Could not remove from an `Arena` using a stale `Key`")
+    }
+
+    /// Remove and return the value associated with the given key,
+    /// without moving any other values.
+    ///
+    /// This leaves a hole in the values, which is skipped while iterating,
+    /// until [`compact`](Arena::compact) is called.
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn try_remove_stable<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<T> {
+        let index = self.slots.try_remove(key)?;
+
+        if index != self.end() {
+            self.keys[Uninit(index)] = MaybeUninit::new(HOLE);
+            self.holes += 1;
+        }
+
+        unsafe { Some(self.values[Uninit(index)].as_ptr().read()) }
+    }
+
+    /// Remove all holes left by [`remove_stable`](Arena::remove_stable),
+    /// while preserving the order of the remaining values.
+    pub fn compact(&mut self) {
+        if self.holes == 0 {
+            return
+        }
+
+        let end = self.end();
+        let mut len = 0;
+
+        for i in 0..end {
+            let back_ref = self.keys[Init(i)];
+
+            if back_ref == HOLE {
+                continue
+            }
+
+            if i != len {
+                unsafe {
+                    let ptr = self.values.inner.as_mut_ptr().cast::<T>();
+                    ptr.add(len).copy_from_nonoverlapping(ptr.add(i), 1);
+                    self.keys[Uninit(len)] = MaybeUninit::new(back_ref);
+                    *self.slots.get_unchecked_mut(back_ref) = len;
+                }
+            }
+
+            len += 1;
+        }
+
+        self.holes = 0;
+    }
+
     /// Return a shared reference to the value associated with the given key.
     ///
     /// If the given key is not associated with a value, then None is returned.
@@ -424,6 +775,79 @@ impl<T, I, V: Version> Arena<T, I, V> {
         Some(&self.values[Init(slot)])
     }
 
+    /// Return the position in [`values`](Arena::values) of the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then None is returned.
+    /// Positions stay the same until a value is removed or the arena is compacted,
+    /// so they can be cached to skip looking up the slot on each access.
+    pub fn value_index<K: ArenaKey<I, V>>(&self, key: K) -> Option<usize> { self.slots.get(key).copied() }
+
+    /// Returns all values in the arena, in no particular order
+    ///
+    /// If there are holes left by [`remove_stable`](Arena::remove_stable),
+    /// then None is returned, see [`compact`](Arena::compact).
+    pub fn values(&self) -> Option<&[T]> {
+        if self.holes == 0 {
+            Some(&self.values[Init(..self.slots.len())])
+        } else {
+            None
+        }
+    }
+
+    /// Returns all values in the arena, in no particular order
+    ///
+    /// If there are holes left by [`remove_stable`](Arena::remove_stable),
+    /// then None is returned, see [`compact`](Arena::compact).
+    pub fn values_mut(&mut self) -> Option<&mut [T]> {
+        if self.holes == 0 {
+            Some(&mut self.values[Init(..self.slots.len())])
+        } else {
+            None
+        }
+    }
+
+    /// Returns all values in the arena, along with an iterator over their keys
+    ///
+    /// The `i`th key is associated with the `i`th value, so the values can be
+    /// updated in bulk while the keys are only read where they are needed.
+    ///
+    /// If there are holes left by [`remove_stable`](Arena::remove_stable),
+    /// then None is returned, see [`compact`](Arena::compact).
+    pub fn split_values_keys_mut<'a, K: 'a + BuildArenaKey<I, V>>(
+        &'a mut self,
+    ) -> Option<ValuesKeysMut<'a, T, I, V, K>> {
+        if self.holes == 0 {
+            let len = self.slots.len();
+            let keys = unsafe { keys(&self.keys.inner, &self.slots, 0) };
+            Some((&mut self.values[Init(..len)], keys))
+        } else {
+            None
+        }
+    }
+
+    /// Return a shared reference to the value associated with each of the given keys,
+    /// or `None` for each key that isn't associated with a value
+    ///
+    /// The results are yielded in the same order as the keys
+    pub fn get_batch<'a, K: ArenaKey<I, V> + 'a, Iter: IntoIterator<Item = K>>(
+        &'a self,
+        keys: Iter,
+    ) -> impl Iterator<Item = Option<&'a T>> {
+        keys.into_iter().map(move |key| self.get(key))
+    }
+
+    /// Return a clone of the value associated with the given key,
+    /// or `T::default()` if the key isn't associated with a value
+    pub fn get_or_default<K: ArenaKey<I, V>>(&self, key: K) -> T
+    where
+        T: Default + Clone,
+    {
+        match self.get(key) {
+            Some(value) => value.clone(),
+            None => T::default(),
+        }
+    }
+
     /// Return a unique reference to the value associated with the given key.
     ///
     /// If the given key is not associated with a value, then None is returned.
@@ -432,6 +856,46 @@ impl<T, I, V: Version> Arena<T, I, V> {
         Some(&mut self.values[Init(slot)])
     }
 
+    /// Return unique references to the values associated with each of the given keys.
+    ///
+    /// If any of the given keys is not associated with a value, or if any two keys
+    /// are associated with the same value, then None is returned.
+    pub fn get_disjoint_mut<K: ArenaKey<I, V>, const N: usize>(&mut self, keys: [K; N]) -> Option<[&mut T; N]> {
+        let mut indices = [0; N];
+
+        for (i, key) in keys.iter().enumerate() {
+            let &index = self.slots.get(key)?;
+
+            if indices[..i].contains(&index) {
+                return None
+            }
+
+            indices[i] = index;
+        }
+
+        let ptr = self.values.inner.as_mut_ptr().cast::<T>();
+        Some(indices.map(|index| unsafe { &mut *ptr.add(index) }))
+    }
+
+    /// Return the key associated with a reference to a value in the arena
+    ///
+    /// If `value` doesn't point to a value in the arena, then None is returned.
+    ///
+    /// Zero-sized values don't have a unique address, so this always returns None for them.
+    pub fn key_of<K: BuildArenaKey<I, V>>(&self, value: &T) -> Option<K> {
+        let size = core::mem::size_of::<T>();
+        let offset = (value as *const T as usize).checked_sub(self.values.inner.as_ptr() as usize)?;
+
+        if size == 0 || offset % size != 0 || offset / size >= self.end() {
+            return None
+        }
+
+        match self.keys[Init(offset / size)] {
+            HOLE => None,
+            slot => self.slots.parse_key(slot),
+        }
+    }
+
     /// Return a shared reference to the value associated with the
     /// given key without performing bounds checking, or checks
     /// if there is a value associated to the key
@@ -456,11 +920,40 @@ impl<T, I, V: Version> Arena<T, I, V> {
         &mut self.values[Init(slot)]
     }
 
+    /// Return unique references to the values associated with the two
+    /// given indices without performing bounds checking, or checks
+    /// if there are values associated to the indices
+    ///
+    /// # Safety
+    ///
+    /// `i != j`, and `contains` should return true with both indices.
+    pub unsafe fn get_unchecked_mut2(&mut self, i: usize, j: usize) -> (&mut T, &mut T) {
+        debug_assert_ne!(i, j);
+        let &i = self.slots.get_unchecked(i);
+        let &j = self.slots.get_unchecked(j);
+        let ptr = self.values.inner.as_mut_ptr().cast::<T>();
+        (&mut *ptr.add(i), &mut *ptr.add(j))
+    }
+
     /// Deletes all elements from the arena
     pub fn delete_all(&mut self) {
-        let len = self.slots.len();
+        let end = self.end();
+        let holes = self.holes;
         self.slots.delete_all();
-        unsafe { core::ptr::drop_in_place(&mut self.values[Init(..len)]) }
+        self.holes = 0;
+        unsafe { self.drop_values(end, holes) }
+    }
+
+    /// Deletes all elements from the arena, and resets the version of each slot
+    /// that isn't exhausted, without reducing it's capacity
+    ///
+    /// This allows slots to be reused for longer, but it gives up the protection from
+    /// the ABA problem for all keys created before this call. Those keys may
+    /// alias keys created after this call, and access unrelated values.
+    /// So this should only be used if no keys created before the reset are used after it.
+    pub fn drain_reset(&mut self) {
+        self.delete_all();
+        self.slots.drain_reset();
     }
 
     /// Retain only the elements specified by the predicate.
@@ -468,6 +961,8 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// If the predicate returns for a given element true,
     /// then the element is kept in the arena.
     pub fn retain<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        self.compact();
+
         for i in (0..self.slots.len()).rev() {
             if !f(&mut self.values[Init(i)]) {
                 self.delete(unsafe { crate::TrustedIndex::new(i) });
@@ -477,17 +972,18 @@ impl<T, I, V: Version> Arena<T, I, V> {
 
     /// An iterator over the keys of the arena, in no particular order
     pub fn keys<'a, K: 'a + BuildArenaKey<I, V>>(&'a self) -> Keys<'_, I, V, K> {
-        unsafe { keys(&self.keys.inner, &self.slots) }
+        unsafe { keys(&self.keys.inner, &self.slots, self.holes) }
     }
 
     /// An iterator of shared references to values of the arena,
     /// in no particular order
-    pub fn iter(&self) -> core::slice::Iter<'_, T> { unsafe { iter(&self.values.inner, self.slots.len()) } }
+    pub fn iter(&self) -> Iter<'_, T> { unsafe { iter(&self.values.inner, &self.keys.inner, self.end(), self.holes) } }
 
     /// An iterator of unique references to values of the arena,
     /// in no particular order
-    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
-        unsafe { iter_mut(&mut self.values.inner, self.slots.len()) }
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let end = self.end();
+        unsafe { iter_mut(&mut self.values.inner, &self.keys.inner, end, self.holes) }
     }
 
     /// Return a draining iterator that removes all elements from the
@@ -496,8 +992,15 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Note: Elements are removed even if the iterator is only partially
     /// consumed or not consumed at all.
     pub fn drain(&mut self) -> Drain<'_, T, I, V> {
+        self.compact();
+        let len = self.slots.len();
+
+        // the values are moved out by the iterator, so the arena can be emptied up front,
+        // this way there's no need to fix up the values as they are removed
+        self.slots.delete_all();
+
         Drain {
-            range: 0..self.slots.len(),
+            range: 0..len,
             arena: self,
         }
     }
@@ -511,6 +1014,8 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Note: Elements are removed even if the iterator is only partially
     /// consumed or not consumed at all.
     pub fn drain_filter<F: FnMut(&mut T) -> bool>(&mut self, filter: F) -> DrainFilter<'_, T, I, V, F> {
+        self.compact();
+
         DrainFilter {
             range: 0..self.slots.len(),
             arena: self,
@@ -520,12 +1025,15 @@ impl<T, I, V: Version> Arena<T, I, V> {
     }
 
     /// An iterator of keys and shared references to values of the arena,
-    /// in no particular order, with each key being associated
-    /// to the corrosponding value
+    /// with each key being associated to the corrosponding value
+    ///
+    /// The entries are yielded in the order the values are stored, which is
+    /// the same order as [`iter`](Arena::iter) and [`values`](Arena::values).
+    /// This walks the values and their keys in a single pass.
     pub fn entries<'a, K: 'a + BuildArenaKey<I, V>>(&'a self) -> Entries<'_, T, I, V, K> {
         Entries {
-            iter: unsafe { iter(&self.values.inner, self.slots.len()) },
-            keys: unsafe { keys(&self.keys.inner, &self.slots) },
+            iter: self.iter(),
+            keys: self.keys(),
         }
     }
 
@@ -533,16 +1041,18 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// in no particular order, with each key being associated
     /// to the corrosponding value
     pub fn entries_mut<'a, K: 'a + BuildArenaKey<I, V>>(&'a mut self) -> EntriesMut<'_, T, I, V, K> {
+        let end = self.end();
         EntriesMut {
-            iter: unsafe { iter_mut(&mut self.values.inner, self.slots.len()) },
-            keys: unsafe { keys(&self.keys.inner, &self.slots) },
+            iter: unsafe { iter_mut(&mut self.values.inner, &self.keys.inner, end, self.holes) },
+            keys: unsafe { keys(&self.keys.inner, &self.slots, self.holes) },
         }
     }
 
     /// An iterator of keys and values of the arena,
     /// in no particular order, with each key being associated
     /// to the corrosponding value
-    pub fn into_entries<K: BuildArenaKey<I, V>>(self) -> IntoEntries<T, I, V, K> {
+    pub fn into_entries<K: BuildArenaKey<I, V>>(mut self) -> IntoEntries<T, I, V, K> {
+        self.compact();
         let this = ManuallyDrop::new(self);
         unsafe {
             IntoEntries {
@@ -553,14 +1063,32 @@ impl<T, I, V: Version> Arena<T, I, V> {
     }
 }
 
-unsafe fn iter<T>(slice: &[MaybeUninit<T>], len: usize) -> core::slice::Iter<'_, T> {
-    let ptr = slice as *const [MaybeUninit<T>] as *const T;
-    core::slice::from_raw_parts(ptr, len).iter()
+unsafe fn iter<'a, T>(
+    slice: &'a [MaybeUninit<T>],
+    keys: &'a [MaybeUninit<usize>],
+    end: usize,
+    holes: usize,
+) -> Iter<'a, T> {
+    let keys = keys.get_unchecked(..end);
+    Iter {
+        values: slice.get_unchecked(..end).iter(),
+        keys: core::slice::from_raw_parts(keys.as_ptr().cast::<usize>(), end).iter(),
+        holes,
+    }
 }
 
-unsafe fn iter_mut<T>(slice: &mut [MaybeUninit<T>], len: usize) -> core::slice::IterMut<'_, T> {
-    let ptr = slice as *mut [MaybeUninit<T>] as *mut T;
-    core::slice::from_raw_parts_mut(ptr, len).iter_mut()
+unsafe fn iter_mut<'a, T>(
+    slice: &'a mut [MaybeUninit<T>],
+    keys: &'a [MaybeUninit<usize>],
+    end: usize,
+    holes: usize,
+) -> IterMut<'a, T> {
+    let keys = keys.get_unchecked(..end);
+    IterMut {
+        values: slice.get_unchecked_mut(..end).iter_mut(),
+        keys: core::slice::from_raw_parts(keys.as_ptr().cast::<usize>(), end).iter(),
+        holes,
+    }
 }
 
 unsafe fn into_iter<T>(slice: Box<[MaybeUninit<T>]>, len: usize) -> std::vec::IntoIter<T> {
@@ -573,14 +1101,16 @@ unsafe fn into_iter<T>(slice: Box<[MaybeUninit<T>]>, len: usize) -> std::vec::In
 unsafe fn keys<'a, I, V: Version, K: BuildArenaKey<I, V>>(
     keys: &'a [MaybeUninit<usize>],
     slots: &'a SparseArena<usize, I, V>,
+    holes: usize,
 ) -> Keys<'a, I, V, K> {
-    let len = slots.len();
+    let len = slots.len() + holes;
     let keys = keys.get_unchecked(..len);
     let keys = core::slice::from_raw_parts(keys.as_ptr().cast::<usize>(), keys.len());
 
     Keys {
         keys: keys.iter().copied(),
         slots,
+        holes,
         key: PhantomData,
     }
 }
@@ -598,6 +1128,7 @@ unsafe fn into_keys<I, V: Version, K: BuildArenaKey<I, V>>(
     IntoKeys {
         keys: keys.into_iter(),
         slots,
+        holes: 0,
         key: PhantomData,
     }
 }
@@ -606,7 +1137,8 @@ impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
     type Item = T;
     type IntoIter = std::vec::IntoIter<T>;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.compact();
         unsafe {
             let mut this = ManuallyDrop::new(self);
             let iter = into_iter(core::ptr::read(&this.values.inner), this.slots.len());
@@ -617,6 +1149,16 @@ impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
     }
 }
 
+#[cfg(feature = "pui-core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pui")))]
+impl<T, I: pui_core::OneShotIdentifier, V: Version> Arena<T, I, V> {
+    /// An iterator over the ids of the arena, in no particular order
+    ///
+    /// This is the same as `keys::<pui_vec::Id<_>>()`, these ids
+    /// elide bounds checks when they are used to access the arena
+    pub fn ids(&self) -> Keys<'_, I, V, pui_vec::Id<I::Token>> { self.keys() }
+}
+
 impl<T, I, V: Version, K: ArenaKey<I, V>> Index<K> for Arena<T, I, V> {
     type Output = T;
 
@@ -635,6 +1177,8 @@ impl<T, I, V: Version> Extend<T> for Arena<T, I, V> {
     #[allow(clippy::drop_copy)]
     fn extend<Iter: IntoIterator<Item = T>>(&mut self, iter: Iter) {
         let iter = iter.into_iter();
+        // the lower bound is exact for `ExactSizeIterator`s, so this reserves all three boxes once,
+        // reserving the upper bound instead could vastly over-allocate for iterators like `Filter`
         self.reserve(iter.size_hint().0);
         iter.for_each(move |value| drop::<usize>(self.vacant_entry().insert(value)));
     }
@@ -642,13 +1186,16 @@ impl<T, I, V: Version> Extend<T> for Arena<T, I, V> {
 
 use std::fmt;
 
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.debug_list().entries(self.clone()).finish() }
+}
+
 impl<T: fmt::Debug, I: fmt::Debug, V: Version + fmt::Debug> fmt::Debug for Arena<T, I, V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let keys = &self.keys[Init(..self.slots.len())];
-        let values = &self.values[Init(..self.slots.len())];
+        let keys = &self.keys[Init(..self.end())];
         f.debug_struct("Arena")
             .field("slots", &self.slots)
-            .field("values", &values)
+            .field("values", &self.iter())
             .field("keys", &keys)
             .finish()
     }
@@ -659,14 +1206,26 @@ macro_rules! keys_impl {
         type Item = K;
 
         fn next(&mut self) -> Option<Self::Item> {
-            self.keys.next().map(move |index| {
-                self.slots
-                    .parse_key(index)
-                    .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
-            })
+            loop {
+                let index = self.keys.next()?;
+
+                if index != HOLE {
+                    return self.slots.parse_key(index).or_else(|| unsafe { core::hint::unreachable_unchecked() })
+                }
+
+                self.holes -= 1;
+            }
         }
 
         fn nth(&mut self, n: usize) -> Option<Self::Item> {
+            if self.holes != 0 {
+                for _ in 0..n {
+                    self.next()?;
+                }
+
+                return self.next()
+            }
+
             self.keys.nth(n).map(move |index| {
                 self.slots
                     .parse_key(index)
@@ -674,18 +1233,33 @@ macro_rules! keys_impl {
             })
         }
 
-        fn size_hint(&self) -> (usize, Option<usize>) { self.keys.size_hint() }
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.keys.len() - self.holes;
+            (len, Some(len))
+        }
     };
     (rev) => {
         fn next_back(&mut self) -> Option<Self::Item> {
-            self.keys.next_back().map(move |index| {
-                self.slots
-                    .parse_key(index)
-                    .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
-            })
+            loop {
+                let index = self.keys.next_back()?;
+
+                if index != HOLE {
+                    return self.slots.parse_key(index).or_else(|| unsafe { core::hint::unreachable_unchecked() })
+                }
+
+                self.holes -= 1;
+            }
         }
 
         fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+            if self.holes != 0 {
+                for _ in 0..n {
+                    self.next_back()?;
+                }
+
+                return self.next_back()
+            }
+
             self.keys.nth_back(n).map(move |index| {
                 self.slots
                     .parse_key(index)
@@ -695,13 +1269,28 @@ macro_rules! keys_impl {
     };
 }
 
+/// Returned from [`Arena::split_values_keys_mut`]
+pub type ValuesKeysMut<'a, T, I, V, K> = (&'a mut [T], Keys<'a, I, V, K>);
+
 /// Returned by [`Arena::keys`]
 pub struct Keys<'a, I, V: Version, K> {
     keys: core::iter::Copied<core::slice::Iter<'a, usize>>,
     slots: &'a SparseArena<usize, I, V>,
+    holes: usize,
     key: PhantomData<fn() -> K>,
 }
 
+impl<I, V: Version, K> Clone for Keys<'_, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            slots: self.slots,
+            holes: self.holes,
+            key: PhantomData,
+        }
+    }
+}
+
 impl<'a, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Keys<'a, I, V, K> {
     keys_impl! {}
 }
@@ -716,6 +1305,7 @@ impl<I, V: Version, K: BuildArenaKey<I, V>> core::iter::FusedIterator for Keys<'
 struct IntoKeys<I, V: Version, K> {
     keys: std::vec::IntoIter<usize>,
     slots: SparseArena<usize, I, V>,
+    holes: usize,
     key: PhantomData<fn() -> K>,
 }
 
@@ -730,6 +1320,92 @@ impl<I, V: Version, K: BuildArenaKey<I, V>> DoubleEndedIterator for IntoKeys<I,
 impl<I, V: Version, K: BuildArenaKey<I, V>> ExactSizeIterator for IntoKeys<I, V, K> {}
 impl<I, V: Version, K: BuildArenaKey<I, V>> core::iter::FusedIterator for IntoKeys<I, V, K> {}
 
+macro_rules! iter_impl {
+    ($get:expr) => {
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                let value = self.values.next()?;
+                let &key = self.keys.next()?;
+
+                if self.holes == 0 || key != HOLE {
+                    return Some(unsafe { $get(value) })
+                }
+
+                self.holes -= 1;
+            }
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.values.len() - self.holes;
+            (len, Some(len))
+        }
+    };
+    (rev $get:expr) => {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            loop {
+                let value = self.values.next_back()?;
+                let &key = self.keys.next_back()?;
+
+                if self.holes == 0 || key != HOLE {
+                    return Some(unsafe { $get(value) })
+                }
+
+                self.holes -= 1;
+            }
+        }
+    };
+}
+
+/// Returned by [`Arena::iter`]
+pub struct Iter<'a, T> {
+    values: core::slice::Iter<'a, MaybeUninit<T>>,
+    keys: core::slice::Iter<'a, usize>,
+    holes: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            keys: self.keys.clone(),
+            holes: self.holes,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    iter_impl! { |value: &'a MaybeUninit<T>| &*value.as_ptr() }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    iter_impl! { rev |value: &MaybeUninit<T>| &*value.as_ptr() }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> core::iter::FusedIterator for Iter<'_, T> {}
+
+/// Returned by [`Arena::iter_mut`]
+pub struct IterMut<'a, T> {
+    values: core::slice::IterMut<'a, MaybeUninit<T>>,
+    keys: core::slice::Iter<'a, usize>,
+    holes: usize,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    iter_impl! { |value: &'a mut MaybeUninit<T>| &mut *value.as_mut_ptr() }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    iter_impl! { rev |value: &'a mut MaybeUninit<T>| &mut *value.as_mut_ptr() }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+impl<T> core::iter::FusedIterator for IterMut<'_, T> {}
+
 /// Returned by [`Arena::drain`]
 pub struct Drain<'a, T, I, V: Version> {
     arena: &'a mut Arena<T, I, V>,
@@ -737,7 +1413,12 @@ pub struct Drain<'a, T, I, V: Version> {
 }
 
 impl<T, I, V: Version> Drop for Drain<'_, T, I, V> {
-    fn drop(&mut self) { self.for_each(drop); }
+    fn drop(&mut self) {
+        // the arena is already empty, so even if a destructor panics,
+        // the rest of the values are dropped and the arena stays valid
+        let range = core::mem::replace(&mut self.range, 0..0);
+        unsafe { core::ptr::drop_in_place(&mut self.arena.values[Init(range)]) }
+    }
 }
 
 impl<'a, T, I, V: Version> Iterator for Drain<'a, T, I, V> {
@@ -745,25 +1426,21 @@ impl<'a, T, I, V: Version> Iterator for Drain<'a, T, I, V> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let index = self.range.next()?;
-        unsafe {
-            let key = self.arena.keys[Init(index)];
-            self.arena.slots.delete_unchecked(key);
-            Some(self.arena.remove_unchecked(index))
-        }
+        unsafe { Some(self.arena.values[Uninit(index)].as_ptr().read()) }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.range.size_hint() }
 }
 
 impl<T, I, V: Version> DoubleEndedIterator for Drain<'_, T, I, V> {
     fn next_back(&mut self) -> Option<Self::Item> {
         let index = self.range.next_back()?;
-        unsafe {
-            let key = self.arena.keys[Init(index)];
-            self.arena.slots.delete_unchecked(key);
-            Some(self.arena.remove_unchecked(index))
-        }
+        unsafe { Some(self.arena.values[Uninit(index)].as_ptr().read()) }
     }
 }
 
+impl<T, I, V: Version> ExactSizeIterator for Drain<'_, T, I, V> {}
+
 /// Returned by [`Arena::drain_filter`]
 pub struct DrainFilter<'a, T, I, V: Version, F: FnMut(&mut T) -> bool> {
     arena: &'a mut Arena<T, I, V>,
@@ -866,10 +1543,19 @@ macro_rules! entry_impl {
 
 /// Returned by [`Arena::entries`]
 pub struct Entries<'a, T, I, V: Version, K> {
-    iter: core::slice::Iter<'a, T>,
+    iter: Iter<'a, T>,
     keys: Keys<'a, I, V, K>,
 }
 
+impl<T, I, V: Version, K> Clone for Entries<'_, T, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            keys: self.keys.clone(),
+        }
+    }
+}
+
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Entries<'a, T, I, V, K> {
     type Item = (K, &'a T);
 
@@ -885,7 +1571,7 @@ impl<T, I, V: Version, K: BuildArenaKey<I, V>> core::iter::FusedIterator for Ent
 
 /// Returned by [`Arena::entries_mut`]
 pub struct EntriesMut<'a, T, I, V: Version, K> {
-    iter: core::slice::IterMut<'a, T>,
+    iter: IterMut<'a, T>,
     keys: Keys<'a, I, V, K>,
 }
 
@@ -1009,6 +1695,64 @@ mod test {
         assert_eq!(items, [0, 3, 6, 9]);
     }
 
+    #[test]
+    fn get_disjoint_mut() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert((0, 'a'));
+        let b: crate::Key<usize> = arena.insert((1, 'b'));
+        let c: crate::Key<usize> = arena.insert((2, 'c'));
+
+        let [x, y, z] = arena.get_disjoint_mut([a, b, c]).unwrap();
+        core::mem::swap(&mut x.1, &mut y.1);
+        core::mem::swap(&mut y.1, &mut z.1);
+        assert_eq!([arena[a], arena[b], arena[c]], [(0, 'b'), (1, 'c'), (2, 'a')]);
+
+        assert!(arena.get_disjoint_mut([a, b, a]).is_none());
+        arena.remove(b);
+        assert!(arena.get_disjoint_mut([a, b]).is_none());
+        assert!(arena.get_disjoint_mut([c, a]).is_some());
+    }
+
+    #[test]
+    fn drain_reset() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        arena.remove(a);
+        let b: crate::Key<usize> = arena.insert(1);
+        assert_ne!(a, b);
+        let _: crate::Key<usize> = arena.insert(2);
+
+        arena.drain_reset();
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(b), None);
+
+        let mut keys = (0..2).map(|i| arena.insert(i)).collect::<Vec<crate::Key<usize>>>();
+        keys.sort_unstable();
+        assert_eq!(keys[0], a);
+    }
+
+    #[test]
+    fn drain_partial() {
+        let rc = std::rc::Rc::new(());
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..6).map(|_| arena.insert(rc.clone())).collect();
+        arena.remove_stable(keys[2]);
+
+        let mut drain = arena.drain();
+        assert_eq!(drain.len(), 5);
+        drop(drain.next());
+        drop(drain.next_back());
+        drop(drain);
+
+        assert_eq!(std::rc::Rc::strong_count(&rc), 1);
+        assert!(arena.is_empty());
+        assert!(keys.iter().all(|&key| !arena.contains(key)));
+
+        let key: crate::Key<usize> = arena.insert(rc.clone());
+        assert_eq!(arena.len(), 1);
+        assert!(!keys.contains(&key));
+    }
+
     #[test]
     fn iter_keys_insert_only() {
         let mut arena = Arena::new();
@@ -1282,4 +2026,199 @@ mod test {
         assert_eq!(into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
         assert_eq!(rev_into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
     }
+
+    #[test]
+    fn remove_stable() {
+        let mut arena = Arena::new();
+        let ins_keys = (0..10).map(|i| arena.insert(i * 10)).collect::<Vec<usize>>();
+        assert_eq!(arena.remove_stable(ins_keys[2]), 20);
+        assert_eq!(arena.remove_stable(ins_keys[5]), 50);
+        assert_eq!(arena.remove_stable(ins_keys[9]), 90);
+        assert_eq!(arena.try_remove_stable(ins_keys[5]), None);
+        assert_eq!(arena.len(), 7);
+
+        let values = [0, 10, 30, 40, 60, 70, 80];
+        assert!(arena.iter().copied().eq(values.iter().copied()));
+        assert!(arena.iter().rev().copied().eq(values.iter().rev().copied()));
+        assert_eq!(arena.iter().len(), 7);
+        assert!(arena.entries().all(|(key, &value): (usize, _)| arena[key] == value));
+        assert_eq!(arena.keys::<usize>().rev().count(), 7);
+
+        let c: usize = arena.insert(100);
+        let clone = arena.clone();
+        assert!(clone.iter().copied().eq(values.iter().copied().chain(Some(100))));
+
+        arena.compact();
+        assert!(arena.iter().copied().eq(values.iter().copied().chain(Some(100))));
+        assert_eq!(arena[c], 100);
+        assert_eq!(arena.remove(ins_keys[0]), 0);
+        assert_eq!(arena[ins_keys[8]], 80);
+    }
+
+    #[test]
+    fn insert_mut() {
+        let mut arena = Arena::new();
+        let (a, value): (usize, &mut i32) = arena.insert_mut(0);
+        *value += 10;
+        let (b, value): (usize, &mut i32) = arena.insert_mut(1);
+        *value += 20;
+        assert_eq!(arena[a], 10);
+        assert_eq!(arena[b], 21);
+    }
+
+    #[test]
+    fn entries_order() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+        arena.remove_stable(keys[3]);
+
+        let entries = arena.entries().map(|(key, &value)| (key, value));
+        assert!(entries.eq([(keys[0], 0), (keys[4], 4), (keys[2], 2)]));
+        assert!(arena.entries::<crate::Key<usize>>().map(|(_, value)| value).eq(arena.iter()));
+    }
+
+    #[test]
+    fn get_unchecked_mut2() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(0);
+        let _: usize = arena.insert(1);
+        let c: usize = arena.insert(2);
+        arena.remove(0);
+        let (x, y) = unsafe { arena.get_unchecked_mut2(c, 1) };
+        core::mem::swap(x, y);
+        assert_eq!(arena[1], 2);
+        assert_eq!(arena[c], 1);
+        assert!(!arena.contains(a));
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(0);
+        assert!(arena.try_reserve(10).is_ok());
+        assert!(arena.capacity() >= 11);
+
+        let capacity = arena.capacity();
+        assert!(arena.try_reserve(usize::MAX).is_err());
+        assert_eq!(arena.capacity(), capacity);
+        assert_eq!(arena[a], 0);
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn value_index() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(10);
+        let b: usize = arena.insert(20);
+        let c: usize = arena.insert(30);
+
+        let index = arena.value_index(c).unwrap();
+        assert_eq!(arena.values().unwrap()[index], 30);
+        arena.values_mut().unwrap()[index] += 1;
+        assert_eq!(arena[c], 31);
+
+        arena.remove(a);
+        assert_eq!(arena.value_index(a), None);
+        let index = arena.value_index(c).unwrap();
+        assert_eq!(arena.values().unwrap()[index], 31);
+
+        arena.remove_stable(c);
+        assert_eq!(arena.values(), None);
+        arena.compact();
+        assert_eq!(arena.values(), Some(&[20][..]));
+        assert_eq!(arena.value_index(b), Some(0));
+    }
+
+    #[test]
+    fn extend_reserves_once() {
+        let mut arena = Arena::<u32>::new();
+        arena.extend(std::vec![0; 100]);
+        assert_eq!(arena.len(), 100);
+        assert_eq!(arena.capacity(), 100);
+    }
+
+    #[test]
+    fn insert_slice() {
+        use std::{string::String, vec};
+
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(String::from("a"));
+        let b: crate::Key<usize> = arena.insert(String::from("b"));
+        arena.remove(a);
+        arena.remove_stable(b);
+
+        let keys: Vec<crate::Key<usize>> = arena.insert_slice(vec![String::from("c"), String::from("d")]);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena[keys[0]], "c");
+        assert_eq!(arena[keys[1]], "d");
+        assert!(!arena.contains(b));
+
+        assert!(arena.insert_slice::<usize>(Vec::new()).is_empty());
+        assert_eq!(arena.len(), 2);
+    }
+
+    #[test]
+    fn reserve_zero() {
+        let mut arena = Arena::new();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), 0);
+
+        let keys: Vec<usize> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove_stable(keys[1]);
+        let capacity = arena.capacity();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), capacity);
+    }
+
+    #[test]
+    fn clone_from() {
+        use std::string::{String, ToString};
+
+        let mut source = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..6).map(|i| source.insert(i.to_string())).collect();
+        source.remove_stable(keys[1]);
+        source.remove(keys[4]);
+
+        let mut arena = Arena::new();
+        for _ in 0..10 {
+            let _: usize = arena.insert(String::from("old"));
+        }
+        arena.remove_stable(3);
+
+        let capacity = arena.capacity();
+        arena.clone_from(&source);
+        assert_eq!(arena.capacity(), capacity);
+        assert_eq!(arena.len(), source.len());
+
+        for &key in &keys {
+            assert_eq!(arena.get(key), source.get(key));
+        }
+
+        let _: usize = arena.insert(String::new());
+        arena.clone_from(&Arena::new());
+        assert!(arena.is_empty());
+    }
+
+    #[test]
+    fn split_values_keys_mut() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+
+        let (values, split_keys) = arena.split_values_keys_mut::<crate::Key<usize>>().unwrap();
+        values.iter_mut().for_each(|value| *value *= 10);
+        let split_keys: Vec<_> = split_keys.collect();
+        assert_eq!(split_keys.len(), values.len());
+
+        for (&key, &value) in split_keys.iter().zip(arena.values().unwrap()) {
+            assert_eq!(arena.get(key), Some(&value));
+        }
+        assert_eq!(arena.get(keys[4]), Some(&40));
+
+        arena.remove_stable(keys[2]);
+        assert!(arena.split_values_keys_mut::<usize>().is_none());
+    }
 }
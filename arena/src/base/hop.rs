@@ -28,7 +28,10 @@ use core::{
 
 use pui_vec::PuiVec;
 
-use crate::{version::Version, ArenaKey, BuildArenaKey};
+use crate::{
+    version::{DynVersion, ExhaustionPolicy, Tagged, Version},
+    ArenaKey, BuildArenaKey,
+};
 
 mod imp;
 use imp::Slot;
@@ -42,10 +45,12 @@ use iter_unchecked::IteratorUnchecked;
 pub struct Arena<T, I = (), V: Version = crate::version::DefaultVersion> {
     slots: PuiVec<Slot<T, V>, I>,
     num_elements: usize,
+    exhausted: usize,
+    on_exhaustion: ExhaustionPolicy,
 }
 
-impl<T> Default for Arena<T> {
-    fn default() -> Self { Self::new() }
+impl<T, I: Default, V: Version> Default for Arena<T, I, V> {
+    fn default() -> Self { Self::with_ident(I::default()) }
 }
 
 impl<T> Arena<T> {
@@ -58,6 +63,8 @@ impl<T, V: Version> Arena<T, (), V> {
     pub fn clear(&mut self) {
         self.slots.vec_mut().clear();
         let _: usize = self.slots.push(Slot::SENTINEL);
+        self.num_elements = 0;
+        self.exhausted = 0;
     }
 }
 
@@ -66,6 +73,8 @@ impl<T, I, V: Version> Arena<T, I, V> {
     pub fn with_ident(ident: I) -> Self {
         Self {
             num_elements: 0,
+            exhausted: 0,
+            on_exhaustion: ExhaustionPolicy::Retire,
             slots: PuiVec::from_raw_parts(std::vec![Slot::SENTINEL], ident),
         }
     }
@@ -79,19 +88,95 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Returns the number of elements in this arena
     pub fn len(&self) -> usize { self.num_elements }
 
+    /// Returns the number of slots that have exhausted their versions
+    ///
+    /// These slots will never be reused, see [`Version`] for details.
+    pub fn exhausted_slots(&self) -> usize { self.exhausted }
+
+    /// An iterator over the indices of the vacant slots that can be reused
+    ///
+    /// The indices are yielded in the order of the internal list of empty slots,
+    /// which is the order that they would be filled by `insert`. Exhausted slots
+    /// are never yielded.
+    pub fn free_slots(&self) -> FreeSlots<'_, T, V> {
+        let sentinel = &self.slots[0];
+        unsafe {
+            FreeSlots {
+                slots: &self.slots,
+                sentinel: sentinel.other_end(),
+                start: 1,
+                end: 0,
+                next_block: sentinel.next_free(),
+            }
+        }
+    }
+
+    /// Returns what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn on_exhaustion(&self) -> ExhaustionPolicy { self.on_exhaustion }
+
+    /// Set what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn set_on_exhaustion(&mut self, policy: ExhaustionPolicy) { self.on_exhaustion = policy; }
+
+    /// Returns an object safe view of the version of the slot at `index`,
+    /// or `None` if `index` is out of bounds
+    ///
+    /// This is intended for tools that inspect arenas, see [`DynVersion`] for details
+    pub fn dyn_version(&self, index: usize) -> Option<std::boxed::Box<dyn DynVersion>>
+    where
+        V: 'static,
+        V::Save: 'static,
+    {
+        // the sentinel at index 0 isn't a real slot
+        match index {
+            0 => None,
+            _ => self.slots.get(index).map(|slot| std::boxed::Box::new(slot.version()) as _),
+        }
+    }
+
+    /// Check if the slot at `index` was reused since it had the version `old`
+    ///
+    /// Unlike [`Arena::contains`], this is also true if the slot is currently vacant,
+    /// but the value with the version `old` was removed. It's false if `old`
+    /// is the version the next value inserted in the slot will get.
+    /// If `index` is out of bounds, this returns true.
+    ///
+    /// This compares versions for equality, so it may return false if the slot's
+    /// version started over at `old` after a `drain_reset` or `restore_structure`.
+    pub fn is_reused(&self, index: usize, old: V::Save) -> bool {
+        match self.slots.get(index) {
+            // the sentinel at index 0 isn't a real slot
+            Some(slot) if index != 0 => crate::version::is_reused(slot.version(), old),
+            _ => true,
+        }
+    }
+
+    /// Overwrite the version of an occupied slot, this allows testing version exhaustion
+    /// without inserting and removing values from a slot until it's exhausted
+    ///
+    /// # Panics
+    ///
+    /// If the slot at `index` isn't occupied
+    #[cfg(feature = "test-util")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    pub fn force_version(&mut self, index: usize, save: V::Save)
+    where
+        V: crate::version::RestorableVersion,
+    {
+        let slot = &mut self.slots[index];
+        assert!(slot.is_occupied(), "tried to force the version of the vacant slot {}", index);
+        slot.set_version(V::from_saved(save));
+    }
+
     /// Returns the capacity of this arena
-    pub fn capacity(&self) -> usize { self.slots.capacity() }
+    // the sentinel slot is never available for values
+    pub fn capacity(&self) -> usize { self.slots.capacity() - 1 }
 
     /// Reserves capacity for at least additional more elements to be inserted
     /// in the given collection. The collection may reserve more space to avoid
     /// frequent reallocations. After calling reserve, capacity will be greater
     /// than or equal to `self.len() + additional`. Does nothing if capacity is
     /// already sufficient.
-    pub fn reserve(&mut self, additional: usize) {
-        if let Some(additional) = self.capacity().wrapping_sub(self.num_elements).checked_sub(additional) {
-            self.slots.reserve(additional)
-        }
-    }
+    pub fn reserve(&mut self, additional: usize) { self.slots.reserve(additional) }
 
     /// Reserves the minimum capacity for exactly additional more elements
     /// to be inserted in the given collection. After calling reserve_exact,
@@ -101,11 +186,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Note that the allocator may give the collection more space than it
     /// requests. Therefore, capacity can not be relied upon to be precisely
     /// minimal. Prefer reserve if future insertions are expected.
-    pub fn reserve_exact(&mut self, additional: usize) {
-        if let Some(additional) = self.capacity().wrapping_sub(self.num_elements).checked_sub(additional) {
-            self.slots.reserve_exact(additional)
-        }
-    }
+    pub fn reserve_exact(&mut self, additional: usize) { self.slots.reserve_exact(additional) }
 
     /// Check if an index is in bounds, and if it is return a `Key<_, _>` to it
     #[inline]
@@ -128,19 +209,70 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// if needed.
     pub fn insert<K: BuildArenaKey<I, V>>(&mut self, value: T) -> K { self.vacant_entry().insert(value) }
 
+    /// Insert a value in the arena, returning key assigned to the value
+    /// and a unique reference to the inserted value.
+    ///
+    /// This avoids looking up the value again after inserting it.
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(&mut self, value: T) -> (K, &mut T) {
+        self.vacant_entry().insert_mut(value)
+    }
+
     /// Return true if a value is associated with the given key.
-    pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool {
+    pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool { self.find(&key).is_some() }
+
+    /// Return whether each of the given keys is associated with a value
+    ///
+    /// The results are in the same order as the keys
+    pub fn contains_all<K: ArenaKey<I, V>>(&self, keys: &[K]) -> std::vec::Vec<bool> {
+        keys.iter().map(|key| self.contains(key)).collect()
+    }
+
+    /// Validate the key, and return it's index if it's associated with a value
+    fn find<K: ArenaKey<I, V>>(&self, key: &K) -> Option<usize> {
         let is_index_guarnateed_valid = key.validate_ident(self.ident(), crate::Validator::new()).into_inner();
         let index = key.index();
         if !is_index_guarnateed_valid && self.slots.len() <= index {
-            return false
+            return None
         }
 
         let version = unsafe { self.slots.get_unchecked(index).version() };
 
-        match key.version() {
+        let is_associated = match key.version() {
             Some(saved) => version.equals_saved(saved),
             None => version.is_full(),
+        };
+
+        if is_associated {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Classify the given key, explaining why it is or isn't associated with a value
+    ///
+    /// This returns [`KeyStatus::Live`](crate::KeyStatus::Live) if and only if `contains` would return true
+    pub fn classify<K: ArenaKey<I, V>>(&self, key: K) -> crate::KeyStatus {
+        use crate::KeyStatus;
+
+        if !key.is_owned_by(self.ident()) {
+            return KeyStatus::WrongIdent
+        }
+
+        let index = key.index();
+        if self.slots.len() <= index {
+            return KeyStatus::OutOfBounds
+        }
+
+        let version = unsafe { self.slots.get_unchecked(index).version() };
+
+        if version.is_empty() {
+            return KeyStatus::Vacant
+        }
+
+        match key.version() {
+            Some(saved) if !version.equals_saved(saved) => KeyStatus::StaleVersion,
+            _ => KeyStatus::Live,
         }
     }
 
@@ -163,11 +295,27 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// Returns `None` if key is not associated with a value.
     pub fn try_remove<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<T> {
-        if self.contains(&key) {
-            Some(unsafe { self.remove_unchecked(key.index()) })
-        } else {
+        let index = self.find(&key)?;
+        Some(unsafe { self.remove_unchecked(index) })
+    }
+
+    /// Remove and return the value associated with the given key, along with
+    /// the saved version that the next value inserted into it's slot will have
+    ///
+    /// Keys for this slot with an older version are stale. The version is `None`
+    /// if the slot's version is exhausted, so it will never be reused.
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn remove_versioned<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<(T, Option<V::Save>)> {
+        let index = self.find(&key)?;
+        let value = unsafe { self.remove_unchecked(index) };
+        let version = unsafe { self.slots.get_unchecked(index).version() };
+        let version = if version.is_exhausted() {
             None
-        }
+        } else {
+            Some(unsafe { version.mark_full().save() })
+        };
+        Some((value, version))
     }
 
     /// Removes the value associated with the given key.
@@ -177,11 +325,12 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// Returns true if the value was removed, an false otherwise
     pub fn delete<K: ArenaKey<I, V>>(&mut self, key: K) -> bool {
-        if self.contains(&key) {
-            unsafe { self.delete_unchecked(key.index()) }
-            true
-        } else {
-            false
+        match self.find(&key) {
+            Some(index) => {
+                unsafe { self.delete_unchecked(index) }
+                true
+            }
+            None => false,
         }
     }
 
@@ -189,10 +338,30 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// If the given key is not associated with a value, then None is returned.
     pub fn get<K: ArenaKey<I, V>>(&self, key: K) -> Option<&T> {
-        if self.contains(&key) {
-            unsafe { Some(self.get_unchecked(key.index())) }
-        } else {
-            None
+        let index = self.find(&key)?;
+        unsafe { Some(self.get_unchecked(index)) }
+    }
+
+    /// Return a shared reference to the value associated with each of the given keys,
+    /// or `None` for each key that isn't associated with a value
+    ///
+    /// The results are yielded in the same order as the keys
+    pub fn get_batch<'a, K: ArenaKey<I, V> + 'a, Iter: IntoIterator<Item = K>>(
+        &'a self,
+        keys: Iter,
+    ) -> impl Iterator<Item = Option<&'a T>> {
+        keys.into_iter().map(move |key| self.get(key))
+    }
+
+    /// Return a clone of the value associated with the given key,
+    /// or `T::default()` if the key isn't associated with a value
+    pub fn get_or_default<K: ArenaKey<I, V>>(&self, key: K) -> T
+    where
+        T: Default + Clone,
+    {
+        match self.get(key) {
+            Some(value) => value.clone(),
+            None => T::default(),
         }
     }
 
@@ -200,8 +369,41 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// If the given key is not associated with a value, then None is returned.
     pub fn get_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<&mut T> {
-        if self.contains(&key) {
-            unsafe { Some(self.get_unchecked_mut(key.index())) }
+        let index = self.find(&key)?;
+        unsafe { Some(self.get_unchecked_mut(index)) }
+    }
+
+    /// Return unique references to the values associated with each of the given keys.
+    ///
+    /// If any of the given keys is not associated with a value, or if any two keys
+    /// are associated with the same value, then None is returned.
+    pub fn get_disjoint_mut<K: ArenaKey<I, V>, const N: usize>(&mut self, keys: [K; N]) -> Option<[&mut T; N]> {
+        let mut indices = [0; N];
+
+        for (i, key) in keys.iter().enumerate() {
+            let index = self.find(key)?;
+
+            if indices[..i].contains(&index) {
+                return None
+            }
+
+            indices[i] = index;
+        }
+
+        let slots = self.slots.as_mut_ptr();
+        Some(indices.map(|index| unsafe { Slot::get_mut_raw(slots.add(index)) }))
+    }
+
+    /// Return the key associated with a reference to a value in the arena
+    ///
+    /// If `value` doesn't point to a value in the arena, then None is returned.
+    pub fn key_of<K: BuildArenaKey<I, V>>(&self, value: &T) -> Option<K> {
+        let offset = (value as *const T as usize).checked_sub(self.slots.as_ptr() as usize)?;
+        let index = offset / core::mem::size_of::<Slot<T, V>>();
+        let slot = self.slots.get(index)?;
+
+        if slot.holds(value) {
+            slot.parse_key(index, self.slots.ident())
         } else {
             None
         }
@@ -230,30 +432,26 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Deletes all elements from the arena
     pub fn delete_all(&mut self) { self.retain(|_| false) }
 
+    /// Deletes all elements from the arena, and resets the version of each slot
+    /// that isn't exhausted, without reducing it's capacity
+    ///
+    /// This allows slots to be reused for longer, but it gives up the protection from
+    /// the ABA problem for all keys created before this call. Those keys may
+    /// alias keys created after this call, and access unrelated values.
+    /// So this should only be used if no keys created before the reset are used after it.
+    pub fn drain_reset(&mut self) {
+        self.delete_all();
+
+        for slot in self.slots.iter_mut() {
+            slot.reset_version();
+        }
+    }
+
     /// Retain only the elements specified by the predicate.
     ///
     /// If the predicate returns for a given element true,
     /// then the element is kept in the arena.
-    pub fn retain<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
-        let mut i = 0;
-
-        for _ in 0..self.num_elements {
-            unsafe {
-                let slot = self.slots.get_unchecked_mut(i);
-                if slot.is_vacant() {
-                    i = 1 + slot.other_end();
-                }
-
-                let value = self.slots.get_unchecked_mut(i).get_mut_unchecked();
-
-                if !f(value) {
-                    self.delete_unchecked(i);
-                }
-            }
-
-            i += 1;
-        }
-    }
+    pub fn retain<F: FnMut(&mut T) -> bool>(&mut self, f: F) { self.retain_runs(f) }
 
     /// An iterator over the keys of the arena, in no particular order
     pub fn keys<K: BuildArenaKey<I, V>>(&self) -> Keys<'_, T, I, V, K> {
@@ -284,11 +482,51 @@ impl<T, I, V: Version> Arena<T, I, V> {
         }
     }
 
+    fn resume(&self, IterState { index, len }: IterState) -> Occupied<'_, T, V> {
+        let index = index.min(self.slots.len());
+        // a stale state may point into the middle of a vacant block, which doesn't
+        // know where it ends, so find the next occupied slot by hand
+        let index = self.slots[index..]
+            .iter()
+            .position(Slot::is_occupied)
+            .map_or(self.slots.len(), |offset| index + offset);
+
+        Occupied {
+            len,
+            slots: iter_unchecked::Iter::new(&self.slots[index..]).enumerate_from(index),
+        }
+    }
+
+    /// Resume an iterator of shared references to values of the arena
+    /// from a state saved by [`Iter::into_state`] or [`Entries::into_state`]
+    ///
+    /// If the arena was modified since the state was saved, then the
+    /// iterator may skip or repeat values, or stop early.
+    pub fn resume_iter(&self, state: IterState) -> Iter<'_, T, V> {
+        Iter {
+            slots: self.resume(state),
+        }
+    }
+
+    /// Resume an iterator of keys and shared references to values of the arena
+    /// from a state saved by [`Iter::into_state`] or [`Entries::into_state`]
+    ///
+    /// If the arena was modified since the state was saved, then the
+    /// iterator may skip or repeat values, or stop early.
+    pub fn resume_entries<K: BuildArenaKey<I, V>>(&self, state: IterState) -> Entries<'_, T, I, V, K> {
+        Entries {
+            slots: self.resume(state),
+            ident: self.slots.ident(),
+            key: PhantomData,
+        }
+    }
+
     fn cursor(&mut self) -> Cursor<'_, T, V> {
         Cursor {
             range: 0..self.slots.len(),
             slots: &mut self.slots,
             num_elements: &mut self.num_elements,
+            exhausted: &mut self.exhausted,
         }
     }
 
@@ -344,6 +582,18 @@ impl<T, I, V: Version> Arena<T, I, V> {
         }
     }
 
+    /// An iterator of slot indices and shared references to values of the arena,
+    /// in no particular order
+    ///
+    /// This is the same as `entries::<usize>()`, which doesn't construct versioned keys
+    pub fn indexed(&self) -> Entries<'_, T, I, V, usize> { self.entries() }
+
+    /// An iterator of slot indices and unique references to values of the arena,
+    /// in no particular order
+    ///
+    /// This is the same as `entries_mut::<usize>()`, which doesn't construct versioned keys
+    pub fn indexed_mut(&mut self) -> EntriesMut<'_, T, I, V, usize> { self.entries_mut() }
+
     /// An iterator of keys and values of the arena,
     /// in no particular order, with each key being associated
     /// to the corrosponding value
@@ -358,6 +608,20 @@ impl<T, I, V: Version> Arena<T, I, V> {
             key: PhantomData,
         }
     }
+
+    /// An iterator of the values of the arena, in no particular order
+    ///
+    /// This is the same as `into_iter`, but doesn't need `IntoIterator` in scope
+    pub fn into_values(self) -> IntoIter<T, V> { self.into_iter() }
+}
+
+impl<T, I, V: Version, const BITS: u32> Arena<T, I, Tagged<V, BITS>> {
+    /// Insert a value in the arena with the given tag, returning key assigned to the value.
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(&mut self, tag: u32, value: T) -> K {
+        self.vacant_entry().insert_tagged(tag, value)
+    }
 }
 
 impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
@@ -374,6 +638,16 @@ impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
     }
 }
 
+#[cfg(feature = "pui-core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pui")))]
+impl<T, I: pui_core::OneShotIdentifier, V: Version> Arena<T, I, V> {
+    /// An iterator over the ids of the arena, in no particular order
+    ///
+    /// This is the same as `keys::<pui_vec::Id<_>>()`, these ids
+    /// elide bounds checks when they are used to access the arena
+    pub fn ids(&self) -> Keys<'_, T, I, V, pui_vec::Id<I::Token>> { self.keys() }
+}
+
 impl<T, I, V: Version, K: ArenaKey<I, V>> Index<K> for Arena<T, I, V> {
     type Output = T;
 
@@ -388,6 +662,7 @@ impl<T, I, V: Version, K: ArenaKey<I, V>> IndexMut<K> for Arena<T, I, V> {
     }
 }
 
+#[derive(Clone)]
 struct OccupiedBase<I> {
     len: usize,
     slots: iter_unchecked::Enumerate<I>,
@@ -397,6 +672,37 @@ type Occupied<'a, T, V> = OccupiedBase<iter_unchecked::Iter<'a, Slot<T, V>>>;
 type OccupiedMut<'a, T, V> = OccupiedBase<iter_unchecked::IterMut<'a, Slot<T, V>>>;
 type IntoOccupied<T, V> = OccupiedBase<iter_unchecked::IntoIter<Slot<T, V>>>;
 
+impl<I: IteratorUnchecked> OccupiedBase<I> {
+    /// Advance to the next occupied slot, returns false if there are no more occupied slots
+    unsafe fn skip_vacant(&mut self) -> bool {
+        // a resumed iterator may have a stale `len`, so make sure not to run off the end
+        while self.slots.len() != 0 {
+            let index = self.slots.index();
+            let slot = self.slots.peek();
+            if slot.is_vacant() {
+                let skip = slot.other_end().wrapping_sub(index).wrapping_add(1);
+                self.slots.advance(skip);
+            } else {
+                return true
+            }
+        }
+
+        false
+    }
+
+    fn into_state(mut self) -> IterState {
+        // move to the next occupied slot, so that resuming doesn't need to skip any vacant slots
+        if self.len != 0 && unsafe { !self.skip_vacant() } {
+            self.len = 0;
+        }
+
+        IterState {
+            index: self.slots.index(),
+            len: self.len,
+        }
+    }
+}
+
 impl<I: IteratorUnchecked> Iterator for OccupiedBase<I> {
     type Item = (usize, I::Item);
 
@@ -404,15 +710,11 @@ impl<I: IteratorUnchecked> Iterator for OccupiedBase<I> {
         self.len = self.len.checked_sub(1)?;
 
         unsafe {
-            loop {
-                let index = self.slots.index();
-                let slot = self.slots.peek();
-                if slot.is_vacant() {
-                    let skip = slot.other_end().wrapping_sub(index).wrapping_add(1);
-                    self.slots.advance(skip);
-                } else {
-                    return Some(self.slots.next())
-                }
+            if self.skip_vacant() {
+                Some(self.slots.next())
+            } else {
+                self.len = 0;
+                None
             }
         }
     }
@@ -427,7 +729,8 @@ impl<I: IteratorUnchecked> DoubleEndedIterator for OccupiedBase<I> {
         self.len = self.len.checked_sub(1)?;
 
         unsafe {
-            loop {
+            // a resumed iterator may have a stale `len`, so make sure not to run off the end
+            while self.slots.len() != 0 {
                 let index = self.slots.index_back();
                 let slot = self.slots.peek_back();
                 if slot.is_vacant() {
@@ -438,6 +741,59 @@ impl<I: IteratorUnchecked> DoubleEndedIterator for OccupiedBase<I> {
                 }
             }
         }
+
+        self.len = 0;
+        None
+    }
+}
+
+/// Returned by [`Arena::free_slots`]
+pub struct FreeSlots<'a, T, V: Version> {
+    slots: &'a [Slot<T, V>],
+    // the block after the sentinel is filled from the back
+    sentinel: usize,
+    // every other block is filled from the front
+    start: usize,
+    end: usize,
+    next_block: usize,
+}
+
+impl<T, V: Version> Clone for FreeSlots<'_, T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots,
+            sentinel: self.sentinel,
+            start: self.start,
+            end: self.end,
+            next_block: self.next_block,
+        }
+    }
+}
+
+impl<T, V: Version> Iterator for FreeSlots<'_, T, V> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.sentinel != 0 {
+            let index = self.sentinel;
+            self.sentinel -= 1;
+            return Some(index)
+        }
+
+        if self.start > self.end {
+            if self.next_block == 0 {
+                return None
+            }
+
+            let slot = unsafe { self.slots.get_unchecked(self.next_block) };
+            self.start = self.next_block;
+            self.end = unsafe { slot.other_end() };
+            self.next_block = unsafe { slot.next_free() };
+        }
+
+        let index = self.start;
+        self.start += 1;
+        Some(index)
     }
 }
 
@@ -446,6 +802,14 @@ pub struct Keys<'a, T, I, V: Version, K> {
     entries: Entries<'a, T, I, V, K>,
 }
 
+impl<T, I, V: Version, K> Clone for Keys<'_, T, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Keys<'a, T, I, V, K> {
     type Item = K;
 
@@ -472,11 +836,34 @@ unsafe fn entry<I, V: Version, T, K: BuildArenaKey<I, V>>(ident: &I) -> impl '_
     move |(index, (version, value))| (K::new_unchecked(index, version.save(), ident), value)
 }
 
+/// The saved position of an [`Iter`] or [`Entries`], see [`Arena::resume_iter`]
+///
+/// Only the front of the iterator is saved, so a resumed iterator
+/// yields all remaining values up to the end of the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IterState {
+    index: usize,
+    len: usize,
+}
+
 /// Returned by [`Arena::iter`]
 pub struct Iter<'a, T, V: Version> {
     slots: Occupied<'a, T, V>,
 }
 
+impl<T, V: Version> Iter<'_, T, V> {
+    /// Save the position of this iterator, so that it can be resumed with [`Arena::resume_iter`]
+    pub fn into_state(self) -> IterState { self.slots.into_state() }
+}
+
+impl<T, V: Version> Clone for Iter<'_, T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+        }
+    }
+}
+
 impl<'a, T, V: Version> Iterator for Iter<'a, T, V> {
     type Item = &'a T;
 
@@ -544,37 +931,43 @@ impl<T, V: Version> core::iter::FusedIterator for IntoIter<T, V> {}
 struct Cursor<'a, T, V: Version> {
     slots: &'a mut [Slot<T, V>],
     num_elements: &'a mut usize,
+    exhausted: &'a mut usize,
     range: core::ops::Range<usize>,
 }
 
 impl<T, V: Version> Cursor<'_, T, V> {
     fn next(&mut self) -> Option<(usize, &mut T)> {
-        let mut index = self.range.next()?;
-
-        let slot = unsafe { self.slots.get_unchecked_mut(index) };
-        if slot.is_vacant() {
-            self.range.start = unsafe { slot.other_end() };
-            index = self.range.next()?;
+        loop {
+            let index = self.range.next()?;
+
+            // a vacant slot either starts a block, so `other_end` is the end of the block, or it ends a
+            // block that was merged with the slots that were just removed, so `other_end` is behind us
+            let slot = unsafe { self.slots.get_unchecked(index) };
+            if slot.is_vacant() {
+                self.range.start = self.range.start.max(unsafe { slot.other_end() } + 1);
+            } else {
+                return Some((index, unsafe { self.slots.get_unchecked_mut(index).get_mut_unchecked() }))
+            }
         }
-
-        Some((index, unsafe { slot.get_mut_unchecked() }))
     }
 
     fn next_back(&mut self) -> Option<(usize, &mut T)> {
-        let mut index = self.range.next_back()?;
-
-        let slot = unsafe { self.slots.get_unchecked_mut(index) };
-        if slot.is_vacant() {
-            self.range.start = unsafe { slot.other_end() };
-            index = self.range.next_back()?;
+        loop {
+            let index = self.range.next_back()?;
+
+            // same as `next`, but the block is entered from it's end
+            let slot = unsafe { self.slots.get_unchecked(index) };
+            if slot.is_vacant() {
+                self.range.end = self.range.end.min(unsafe { slot.other_end() });
+            } else {
+                return Some((index, unsafe { self.slots.get_unchecked_mut(index).get_mut_unchecked() }))
+            }
         }
-
-        Some((index, unsafe { slot.get_mut_unchecked() }))
     }
 
     unsafe fn take(&mut self, index: usize) -> T {
         *self.num_elements -= 1;
-        imp::remove_unchecked(self.slots, index)
+        imp::remove_unchecked(self.slots, index, self.exhausted)
     }
 }
 
@@ -655,6 +1048,21 @@ pub struct Entries<'a, T, I, V: Version, K> {
     key: PhantomData<fn() -> K>,
 }
 
+impl<T, I, V: Version, K> Entries<'_, T, I, V, K> {
+    /// Save the position of this iterator, so that it can be resumed with [`Arena::resume_entries`]
+    pub fn into_state(self) -> IterState { self.slots.into_state() }
+}
+
+impl<T, I, V: Version, K> Clone for Entries<'_, T, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            ident: self.ident,
+            key: PhantomData,
+        }
+    }
+}
+
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Entries<'a, T, I, V, K> {
     type Item = (K, &'a T);
 
@@ -812,6 +1220,39 @@ mod test {
         assert_eq!(items, [0, 3, 6, 9]);
     }
 
+    #[test]
+    fn drain_reset() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        arena.remove(a);
+        let b: crate::Key<usize> = arena.insert(1);
+        assert_ne!(a, b);
+        let _: crate::Key<usize> = arena.insert(2);
+
+        arena.drain_reset();
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(b), None);
+
+        let mut keys = (0..2).map(|i| arena.insert(i)).collect::<Vec<crate::Key<usize>>>();
+        keys.sort_unstable();
+        assert_eq!(keys[0], a);
+    }
+
+    #[test]
+    fn indexed() {
+        let mut arena = Arena::new();
+        let mut ins_keys = (0..10).map(|i| arena.insert(i * 10)).collect::<Vec<crate::Key<usize>>>();
+        for i in (0..ins_keys.len()).rev().step_by(3) {
+            let key = ins_keys.remove(i);
+            arena.remove(key);
+        }
+        for (index, value) in arena.indexed_mut() {
+            *value += index;
+        }
+        let indexed = arena.indexed().map(|(index, &value)| (index, value)).collect::<Vec<_>>();
+        assert_eq!(indexed, [(2, 12), (3, 23), (5, 45), (6, 56), (8, 78), (9, 89)]);
+    }
+
     #[test]
     fn iter_keys_insert_only() {
         let mut arena = Arena::new();
@@ -1085,4 +1526,215 @@ mod test {
         assert_eq!(into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
         assert_eq!(rev_into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
     }
+
+    #[test]
+    fn insert_mut() {
+        let mut arena = Arena::new();
+        let (a, value): (usize, &mut i32) = arena.insert_mut(0);
+        *value += 10;
+        let (b, value): (usize, &mut i32) = arena.insert_mut(1);
+        *value += 20;
+        assert_eq!(arena[a], 10);
+        assert_eq!(arena[b], 21);
+    }
+
+    #[test]
+    fn resume_iter() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..10).map(|i| arena.insert(i)).collect();
+        for &key in &keys[3..6] {
+            arena.remove(key);
+        }
+
+        let mut iter = arena.iter();
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        let state = iter.into_state();
+
+        let mut iter = arena.resume_iter(state);
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&6));
+        assert_eq!(iter.next_back(), Some(&9));
+        let state = iter.into_state();
+
+        let entries = arena.resume_entries::<usize>(state);
+        assert!(entries.map(|(key, &value)| (key, value)).eq([(keys[7], 7), (keys[8], 8)]));
+
+        arena.clear();
+        assert_eq!(arena.resume_iter(state).next(), None);
+    }
+
+    #[test]
+    fn double_ended() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..10).map(|i| arena.insert(i)).collect();
+        for &key in &keys[5..9] {
+            arena.remove(key);
+        }
+
+        let mut iter = arena.iter();
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+        assert_eq!(iter.next_back(), Some(&9));
+        assert_eq!(iter.next_back(), Some(&4));
+        assert_eq!(iter.next_back(), Some(&3));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut arena = Arena::new();
+        assert_eq!(arena.capacity(), 0);
+        arena.reserve(10);
+        let capacity = arena.capacity();
+        assert!(capacity >= 10);
+
+        for i in 0..capacity {
+            let _: usize = arena.insert(i);
+        }
+
+        assert_eq!(arena.capacity(), capacity);
+
+        let mut arena = Arena::new();
+        arena.reserve_exact(10);
+        let capacity = arena.capacity();
+        assert!(capacity >= 10);
+
+        for i in 0..capacity {
+            let _: usize = arena.insert(i);
+        }
+
+        assert_eq!(arena.capacity(), capacity);
+    }
+
+    #[test]
+    fn drain_skips_vacant() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..6).map(|i| arena.insert(i)).collect();
+        for &i in &[0, 1, 4] {
+            arena.remove(keys[i]);
+        }
+        assert_eq!(arena.drain().collect::<Vec<_>>(), [2, 3, 5]);
+        assert!(arena.is_empty());
+
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..6).map(|i| arena.insert(i)).collect();
+        for &i in &[1, 4, 5] {
+            arena.remove(keys[i]);
+        }
+        assert_eq!(arena.drain().rev().collect::<Vec<_>>(), [3, 2, 0]);
+    }
+
+    #[test]
+    fn into_values() {
+        let value = std::rc::Rc::new(());
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..4).map(|_| arena.insert(value.clone())).collect();
+        arena.remove(keys[1]);
+        assert_eq!(std::rc::Rc::strong_count(&value), 4);
+
+        let mut values = arena.into_values();
+        assert_eq!(values.len(), 3);
+        drop(values.next());
+        assert_eq!(std::rc::Rc::strong_count(&value), 3);
+        drop(values);
+        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn dyn_version() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(0);
+        let b: usize = arena.insert(1);
+        arena.remove(b);
+
+        assert!(arena.dyn_version(0).is_none());
+        assert!(arena.dyn_version(a).unwrap().full());
+        assert!(arena.dyn_version(a).unwrap().save_boxed().is_some());
+        assert!(!arena.dyn_version(b).unwrap().full());
+        assert!(arena.dyn_version(b).unwrap().save_boxed().is_none());
+        assert!(arena.dyn_version(b + 1).is_none());
+    }
+
+    #[test]
+    fn free_slots() {
+        let mut arena = Arena::new();
+        assert_eq!(arena.free_slots().count(), 0);
+        let keys: Vec<usize> = (0..10).map(|i| arena.insert(i)).collect();
+        for &i in &[1, 0, 5, 8, 6, 3] {
+            arena.remove(keys[i]);
+        }
+
+        let free: Vec<_> = arena.free_slots().collect();
+        assert_eq!(free.len(), 6);
+        for &index in &free {
+            assert_eq!(arena.insert::<usize>(0), index);
+        }
+        assert_eq!(arena.free_slots().count(), 0);
+    }
+
+    #[test]
+    fn free_list_new_blocks() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..6).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+        arena.remove(keys[3]);
+        // prepending to the older block must keep the newer block in the freelist
+        arena.remove(keys[0]);
+
+        let mut free: Vec<_> = arena.free_slots().collect();
+        free.sort_unstable();
+        assert_eq!(free, [1, 2, 4]);
+    }
+
+    #[test]
+    fn retain_runs() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..100).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[50]);
+        arena.remove(keys[99]);
+
+        arena.retain(|&mut i| !(25..75).contains(&i) && i % 10 != 3);
+        assert_eq!(arena.len(), 44);
+        assert!(arena.iter().all(|&i| !(25..75).contains(&i) && i % 10 != 3));
+        assert_eq!(arena.free_slots().count(), 56);
+
+        let capacity = arena.capacity();
+        for i in 0..56 {
+            let _: usize = arena.insert(i);
+        }
+        assert_eq!(arena.capacity(), capacity);
+        assert_eq!(arena.free_slots().count(), 0);
+        assert_eq!(arena.len(), 100);
+    }
+
+    #[test]
+    fn default_ident() {
+        #[derive(Default)]
+        struct Ident;
+
+        let mut arena = Arena::<_, Ident>::default();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b: crate::Key<usize> = arena.insert(1);
+        assert_eq!(arena.remove(a), 0);
+        assert_eq!(arena[b], 1);
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn reserve_zero() {
+        let mut arena = Arena::new();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), 0);
+
+        let keys: Vec<usize> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+        let capacity = arena.capacity();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), capacity);
+    }
 }
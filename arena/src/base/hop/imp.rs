@@ -4,7 +4,7 @@ use core::{
 };
 
 use super::{Arena, BuildArenaKey};
-use crate::version::{DefaultVersion, Version};
+use crate::version::{DefaultVersion, Tagged, Version};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
@@ -131,14 +131,35 @@ impl<T, V: Version> Slot<T, V> {
 
     pub(super) fn version(&self) -> V { self.version }
 
+    #[cfg(feature = "test-util")]
+    pub(super) fn set_version(&mut self, version: V) { self.version = version }
+
+    /// reset the version of a vacant slot, unless it's exhausted
+    pub(super) fn reset_version(&mut self) {
+        if !self.version.is_exhausted() {
+            self.version = V::EMPTY;
+        }
+    }
+
     pub(super) unsafe fn get_unchecked(&self) -> &T { &*self.data.value }
 
     pub(super) unsafe fn get_mut_unchecked(&mut self) -> &mut T { &mut *self.data.value }
 
+    /// Get the value in `slot` without creating a reference to the whole slot,
+    /// so other slots in the same arena can be borrowed at the same time
+    pub(super) unsafe fn get_mut_raw<'a>(slot: *mut Self) -> &'a mut T { &mut (*slot).data.value }
+
+    /// Returns true if this slot holds `value`
+    pub(super) fn holds(&self, value: &T) -> bool {
+        self.is_occupied() && core::ptr::eq(unsafe { self.get_unchecked() }, value)
+    }
+
     pub(super) unsafe fn take_unchecked(&mut self) -> T { ManuallyDrop::take(&mut self.data.value) }
 
     pub(super) unsafe fn other_end(&self) -> usize { self.data.free.other_end }
 
+    pub(super) unsafe fn next_free(&self) -> usize { self.data.free.next }
+
     pub(super) fn is_occupied(&self) -> bool { self.version.is_full() }
 
     pub(super) fn is_vacant(&self) -> bool { self.version.is_empty() }
@@ -152,44 +173,131 @@ impl<'a, T, I, V: Version> VacantEntry<'a, T, I, V> {
     }
 
     /// Insert an element into the vacant entry
-    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K {
+    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K { self.insert_mut(value).0 }
+
+    /// Insert an element into the vacant entry, and return a unique reference to it
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(self, value: T) -> (K, &'a mut T) {
         unsafe {
-            let slot = self.arena.slots.get_unchecked_mut(self.index);
+            let arena = self.arena;
+            let slot = arena.slots.get_unchecked_mut(self.index);
             slot.data = Data {
                 value: ManuallyDrop::new(value),
             };
             slot.version = self.updated_gen;
-            self.arena.num_elements += 1;
-            remove_slot_from_freelist(&mut self.arena.slots, self.index, self.free);
+            arena.num_elements += 1;
+            remove_slot_from_freelist(&mut arena.slots, self.index, self.free);
 
-            K::new_unchecked(self.index, self.updated_gen.save(), self.arena.slots.ident())
+            let key = K::new_unchecked(self.index, self.updated_gen.save(), arena.slots.ident());
+            (key, arena.slots.get_unchecked_mut(self.index).get_mut_unchecked())
         }
     }
 }
 
+impl<T, I, V: Version, const BITS: u32> VacantEntry<'_, T, I, Tagged<V, BITS>> {
+    /// Insert an element into the vacant entry with the given tag
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(mut self, tag: u32, value: T) -> K {
+        self.updated_gen = self.updated_gen.with_tag(tag);
+        self.insert(value)
+    }
+}
+
 impl<T, I, V: Version> Arena<T, I, V> {
     pub(super) unsafe fn remove_unchecked(&mut self, index: usize) -> T {
         self.num_elements -= 1;
-        remove_unchecked(&mut self.slots, index)
+        remove_unchecked(&mut self.slots, index, &mut self.exhausted)
     }
 
     pub(super) unsafe fn delete_unchecked(&mut self, index: usize) {
-        struct Fixup<'a, T, V: Version>(&'a mut [Slot<T, V>], usize);
+        struct Fixup<'a, T, V: Version>(&'a mut [Slot<T, V>], usize, &'a mut usize);
 
         impl<T, V: Version> Drop for Fixup<'_, T, V> {
-            fn drop(&mut self) { unsafe { insert_slot_into_freelist(self.0, self.1) } }
+            fn drop(&mut self) { unsafe { insert_slot_into_freelist(self.0, self.1, self.2) } }
         }
 
         self.num_elements -= 1;
-        let fixup = Fixup(&mut self.slots, index);
+        let fixup = Fixup(&mut self.slots, index, &mut self.exhausted);
         let slot = fixup.0.get_unchecked_mut(index);
         ManuallyDrop::drop(&mut slot.data.value);
     }
 
+    /// Retain only the values for which `f` returns true
+    ///
+    /// Each run of deleted slots is linked into the freelist once, instead of once per slot
+    pub(super) fn retain_runs<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        // the slots `start..index` were deleted, but aren't in the freelist yet
+        struct Run<'a, T, V: Version> {
+            slots: &'a mut [Slot<T, V>],
+            start: usize,
+            index: usize,
+        }
+
+        impl<T, V: Version> Run<'_, T, V> {
+            fn flush(&mut self, next: usize) {
+                if self.start != self.index {
+                    unsafe { insert_block_into_freelist(self.slots, self.start, self.index - 1) }
+                }
+
+                self.start = next;
+                self.index = next;
+            }
+        }
+
+        impl<T, V: Version> Drop for Run<'_, T, V> {
+            fn drop(&mut self) { self.flush(self.index) }
+        }
+
+        let num_elements = &mut self.num_elements;
+        let exhausted = &mut self.exhausted;
+        let mut run = Run {
+            slots: &mut self.slots,
+            start: 0,
+            index: 0,
+        };
+
+        while run.index < run.slots.len() {
+            let index = run.index;
+            let slot = unsafe { run.slots.get_unchecked_mut(index) };
+
+            if slot.is_vacant() {
+                let next = 1 + unsafe { slot.other_end() };
+                run.flush(next);
+                continue
+            }
+
+            if f(unsafe { slot.get_mut_unchecked() }) {
+                run.flush(index + 1);
+                continue
+            }
+
+            *num_elements -= 1;
+            let value = unsafe { slot.take_unchecked() };
+
+            match unsafe { slot.version.mark_empty() } {
+                Ok(version) => {
+                    slot.version = version;
+                    run.index += 1;
+                }
+                Err(version) => {
+                    // exhausted slots are never put in the freelist, so they split the run
+                    slot.version = version;
+                    slot.data.mu_free.other_end = MaybeUninit::new(index);
+                    *exhausted += 1;
+                    run.flush(index + 1);
+                }
+            }
+
+            drop(value);
+        }
+    }
+
     pub(super) fn __vacant_entry(&mut self) -> VacantEntry<'_, T, I, V> {
         #[cold]
         #[inline(never)]
         unsafe fn allocate_new_node<T, I, V: Version>(arena: &mut Arena<T, I, V>, index: usize) {
+            // the sentinel at index 0 isn't a real slot
+            arena.on_exhaustion.check(arena.slots.len() - 1, arena.num_elements);
             arena.slots.push::<usize>(Slot {
                 version: V::EMPTY,
                 data: Data {
@@ -251,10 +359,14 @@ unsafe fn mu_freelist<T, V: Version>(slots: &mut [Slot<T, V>], index: usize) ->
     &mut slots.get_unchecked_mut(index).data.mu_free
 }
 
-pub(super) unsafe fn remove_unchecked<T, V: Version>(slots: &mut [Slot<T, V>], index: usize) -> T {
+pub(super) unsafe fn remove_unchecked<T, V: Version>(
+    slots: &mut [Slot<T, V>],
+    index: usize,
+    exhausted: &mut usize,
+) -> T {
     let slot = slots.get_unchecked_mut(index);
     let value = ManuallyDrop::take(&mut slot.data.value);
-    insert_slot_into_freelist(slots, index);
+    insert_slot_into_freelist(slots, index, exhausted);
     value
 }
 
@@ -294,7 +406,7 @@ unsafe fn remove_slot_from_freelist<T, V: Version>(slots: &mut [Slot<T, V>], ind
     };
 }
 
-unsafe fn insert_slot_into_freelist<T, V: Version>(slots: &mut [Slot<T, V>], index: usize) {
+unsafe fn insert_slot_into_freelist<T, V: Version>(slots: &mut [Slot<T, V>], index: usize, exhausted: &mut usize) {
     let slot = slots.get_unchecked_mut(index);
     match slot.version.mark_empty() {
         Ok(next_version) => slot.version = next_version,
@@ -306,13 +418,24 @@ unsafe fn insert_slot_into_freelist<T, V: Version>(slots: &mut [Slot<T, V>], ind
             // if the current slot is vacant, and then accesses `free.other_end`
             slot.data.mu_free.other_end = MaybeUninit::new(index);
             slot.version = version;
+            *exhausted += 1;
             return
         }
     }
 
-    let left = slots.get_unchecked(index.wrapping_sub(1)).version;
+    insert_block_into_freelist(slots, index, index)
+}
+
+/// Link the slots `start..=end` into the freelist as a single block,
+/// merging it with the blocks next to it
+///
+/// # Safety
+///
+/// The slots `start..=end` must be vacant and not exhausted, and must not be in the freelist
+unsafe fn insert_block_into_freelist<T, V: Version>(slots: &mut [Slot<T, V>], start: usize, end: usize) {
+    let left = slots.get_unchecked(start.wrapping_sub(1)).version;
     let is_left_vacant = left.is_empty() && !left.is_exhausted();
-    let is_right_vacant = slots.get(index.wrapping_add(1)).map_or(false, |slot| {
+    let is_right_vacant = slots.get(end.wrapping_add(1)).map_or(false, |slot| {
         let right = slot.version;
         right.is_empty() && !right.is_exhausted()
     });
@@ -323,39 +446,41 @@ unsafe fn insert_slot_into_freelist<T, V: Version>(slots: &mut [Slot<T, V>], ind
 
             let head = freelist(slots, 0);
             let old_head = head.next;
-            head.next = index;
-            *mu_freelist(slots, index) = FreeNode {
+            head.next = start;
+            freelist(slots, old_head).prev = start;
+            mu_freelist(slots, end).other_end = MaybeUninit::new(start);
+            *mu_freelist(slots, start) = FreeNode {
                 prev: 0,
                 next: old_head,
-                other_end: index,
+                other_end: end,
             }
             .into();
         }
         (false, true) => {
             // prepend
 
-            let front = *freelist(slots, index + 1);
-            *mu_freelist(slots, index) = front.into();
-            let index = MaybeUninit::new(index);
-            mu_freelist(slots, front.other_end).other_end = index;
-            mu_freelist(slots, front.next).prev = index;
-            mu_freelist(slots, front.prev).next = index;
+            let front = *freelist(slots, end + 1);
+            *mu_freelist(slots, start) = front.into();
+            let start = MaybeUninit::new(start);
+            mu_freelist(slots, front.other_end).other_end = start;
+            mu_freelist(slots, front.next).prev = start;
+            mu_freelist(slots, front.prev).next = start;
         }
         (true, false) => {
             // append
 
-            let front = mu_freelist(slots, index - 1).other_end.assume_init();
-            mu_freelist(slots, index).other_end = MaybeUninit::new(front);
-            mu_freelist(slots, front).other_end = MaybeUninit::new(index);
+            let front = mu_freelist(slots, start - 1).other_end.assume_init();
+            mu_freelist(slots, end).other_end = MaybeUninit::new(front);
+            mu_freelist(slots, front).other_end = MaybeUninit::new(end);
         }
         (true, true) => {
             // join
 
-            let next = *freelist(slots, index + 1);
+            let next = *freelist(slots, end + 1);
             mu_freelist(slots, next.prev).next = MaybeUninit::new(next.next);
             mu_freelist(slots, next.next).prev = MaybeUninit::new(next.prev);
 
-            let front = mu_freelist(slots, index - 1).other_end.assume_init();
+            let front = mu_freelist(slots, start - 1).other_end.assume_init();
             let back = next.other_end;
 
             mu_freelist(slots, front).other_end = MaybeUninit::new(back);
@@ -30,8 +30,16 @@ pub(super) trait IteratorUnchecked {
     {
         Enumerate { index: 0, iter: self }
     }
+
+    fn enumerate_from(self, index: usize) -> Enumerate<Self>
+    where
+        Self: Sized,
+    {
+        Enumerate { index, iter: self }
+    }
 }
 
+#[derive(Clone)]
 pub(super) struct Enumerate<I> {
     index: usize,
     iter: I,
@@ -40,7 +48,7 @@ pub(super) struct Enumerate<I> {
 impl<I: IteratorUnchecked> Enumerate<I> {
     pub fn index(&self) -> usize { self.index }
 
-    pub fn index_back(&self) -> usize { self.iter.len() - self.index }
+    pub fn index_back(&self) -> usize { self.iter.len() + self.index }
 }
 
 impl<I: IteratorUnchecked> IteratorUnchecked for Enumerate<I> {
@@ -94,6 +102,16 @@ impl<'a, T> Iter<'a, T> {
     }
 }
 
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            front: self.front,
+            back: self.back,
+            lt: PhantomData,
+        }
+    }
+}
+
 unsafe impl<T: Sync> Send for Iter<'_, T> {}
 unsafe impl<T: Sync> Sync for Iter<'_, T> {}
 
@@ -0,0 +1,485 @@
+//! Inline Arenas - A sparse arena that stores it's first few slots inline
+//!
+//! An inline arena behaves exactly like a [`sparse`](super::sparse) arena, and uses
+//! the same linked-list of empty slots. However, the first `N` slots are stored
+//! inline, like in a `smallvec`, so the first `N` insertions don't touch the
+//! allocator. Once more than `N` slots are needed, all of the slots are moved
+//! onto the heap, and the arena behaves like a sparse arena from then on.
+//!
+//! This is useful for many small, short-lived arenas, but it makes the arena
+//! itself larger. So prefer a sparse arena if you don't create many arenas.
+//!
+//! Inline arenas don't have an identifier, so they can be used with any
+//! `ArenaKey<(), V>`, for example `usize` or `Key<usize, _>`.
+//!
+//! ```rust
+//! use pui_arena::base::inline::Arena;
+//!
+//! let mut arena = Arena::<_, 4>::new();
+//! let a: usize = arena.insert('a');
+//! let b: usize = arena.insert('b');
+//!
+//! assert!(arena.is_inline());
+//! assert_eq!(arena[a], 'a');
+//! assert_eq!(arena.remove(b), 'b');
+//! ```
+
+use core::{
+    marker::PhantomData,
+    mem::{ManuallyDrop, MaybeUninit},
+    ops::{Index, IndexMut},
+    ptr, slice,
+};
+
+use super::sparse::{Data, Entries, Iter, IterMut, Keys, Occupied, Slot};
+use crate::{
+    version::{DefaultVersion, ExhaustionPolicy, Version},
+    ArenaKey, BuildArenaKey,
+};
+
+enum Slots<T, V: Version, const N: usize> {
+    Inline {
+        len: usize,
+        slots: MaybeUninit<[Slot<T, V>; N]>,
+    },
+    Heap(std::vec::Vec<Slot<T, V>>),
+}
+
+impl<T, V: Version, const N: usize> Drop for Slots<T, V, N> {
+    fn drop(&mut self) {
+        if let Self::Inline { .. } = self {
+            unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+        }
+    }
+}
+
+impl<T, V: Version, const N: usize> Slots<T, V, N> {
+    const INIT: Self = Self::Inline {
+        len: 0,
+        slots: MaybeUninit::uninit(),
+    };
+
+    fn as_slice(&self) -> &[Slot<T, V>] {
+        match self {
+            Self::Inline { len, slots } => unsafe { slice::from_raw_parts(slots.as_ptr().cast(), *len) },
+            Self::Heap(slots) => slots,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [Slot<T, V>] {
+        match self {
+            Self::Inline { len, slots } => unsafe { slice::from_raw_parts_mut(slots.as_mut_ptr().cast(), *len) },
+            Self::Heap(slots) => slots,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Inline { .. } => N,
+            Self::Heap(slots) => slots.capacity(),
+        }
+    }
+
+    fn push(&mut self, slot: Slot<T, V>) {
+        match self {
+            Self::Inline { len, slots } if *len < N => unsafe {
+                slots.as_mut_ptr().cast::<Slot<T, V>>().add(*len).write(slot);
+                *len += 1;
+            },
+            Self::Inline { len, slots } => {
+                let mut heap = std::vec::Vec::with_capacity(N.saturating_mul(2).max(4));
+                unsafe {
+                    // the slots are moved onto the heap, so they must not be dropped inline
+                    ptr::copy_nonoverlapping(slots.as_ptr().cast(), heap.as_mut_ptr(), *len);
+                    heap.set_len(*len);
+                    *len = 0;
+                }
+                heap.push(slot);
+                *self = Self::Heap(heap);
+            }
+            Self::Heap(slots) => slots.push(slot),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Self::Inline { len, .. } if *len == 0 => (),
+            Self::Inline { len, slots } => {
+                let to_drop: *mut [Slot<T, V>] = ptr::slice_from_raw_parts_mut(slots.as_mut_ptr().cast(), *len);
+                *len = 0;
+                unsafe { ptr::drop_in_place(to_drop) }
+            }
+            Self::Heap(slots) => slots.clear(),
+        }
+    }
+}
+
+/// An inline arena
+pub struct Arena<T, const N: usize, V: Version = DefaultVersion> {
+    slots: Slots<T, V, N>,
+    next: usize,
+    num_elements: usize,
+    exhausted: usize,
+    on_exhaustion: ExhaustionPolicy,
+}
+
+/// An empty slot in an inline arena
+pub struct VacantEntry<'a, T, const N: usize, V: Version = DefaultVersion> {
+    arena: &'a mut Arena<T, N, V>,
+    new_next: usize,
+}
+
+impl<T, const N: usize, V: Version> Default for Arena<T, N, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<'a, T, const N: usize, V: Version> VacantEntry<'a, T, N, V> {
+    /// Get the key associated with the `VacantEntry`, this key can be used
+    /// once this `VacantEntry` gets filled
+    pub fn key<K: BuildArenaKey<(), V>>(&self) -> K {
+        let index = self.arena.next;
+        unsafe {
+            let slot = self.arena.slots.as_slice().get_unchecked(index);
+            K::new_unchecked(index, slot.version.mark_full().save(), &())
+        }
+    }
+
+    /// Insert an element into the vacant entry
+    pub fn insert<K: BuildArenaKey<(), V>>(self, value: T) -> K { self.insert_mut(value).0 }
+
+    /// Insert an element into the vacant entry, and return a unique reference to it
+    pub fn insert_mut<K: BuildArenaKey<(), V>>(self, value: T) -> (K, &'a mut T) {
+        let arena = self.arena;
+        let index = arena.next;
+        let slot = unsafe { arena.slots.as_mut_slice().get_unchecked_mut(index) };
+        slot.data = Data {
+            value: ManuallyDrop::new(value),
+        };
+        slot.version = unsafe { slot.version.mark_full() };
+        let version = unsafe { slot.version.save() };
+        arena.next = self.new_next;
+        arena.num_elements += 1;
+
+        let key = unsafe { K::new_unchecked(index, version, &()) };
+        (key, unsafe { &mut *slot.data.value })
+    }
+}
+
+impl<T, const N: usize, V: Version> Arena<T, N, V> {
+    /// An empty arena
+    pub const INIT: Self = Self {
+        slots: Slots::INIT,
+        next: 0,
+        num_elements: 0,
+        exhausted: 0,
+        on_exhaustion: ExhaustionPolicy::Retire,
+    };
+
+    /// Create a new arena
+    pub const fn new() -> Self { Self::INIT }
+
+    /// Returns true if the arena is empty
+    pub fn is_empty(&self) -> bool { self.num_elements == 0 }
+
+    /// Returns the number of elements in this arena
+    pub fn len(&self) -> usize { self.num_elements }
+
+    /// Returns true if the slots are still stored inline
+    pub fn is_inline(&self) -> bool { matches!(self.slots, Slots::Inline { .. }) }
+
+    /// Returns the number of slots that have exhausted their versions
+    ///
+    /// These slots will never be reused, see [`Version`] for details.
+    pub fn exhausted_slots(&self) -> usize { self.exhausted }
+
+    /// Returns what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn on_exhaustion(&self) -> ExhaustionPolicy { self.on_exhaustion }
+
+    /// Set what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn set_on_exhaustion(&mut self, policy: ExhaustionPolicy) { self.on_exhaustion = policy; }
+
+    /// Returns the capacity of this arena, this is `N` until the slots are moved onto the heap
+    pub fn capacity(&self) -> usize { self.slots.capacity() }
+
+    /// Clear the arena without reducing it's capacity
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.num_elements = 0;
+        self.exhausted = 0;
+        self.slots.clear();
+    }
+
+    /// Check if an index is in bounds, and if it is return a `Key<_, _>` to it
+    pub fn parse_key<K: BuildArenaKey<(), V>>(&self, index: usize) -> Option<K> {
+        let slot = self.slots.as_slice().get(index)?;
+        if slot.version.is_full() {
+            Some(unsafe { K::new_unchecked(index, slot.version.save(), &()) })
+        } else {
+            None
+        }
+    }
+
+    /// Return a handle to a vacant entry allowing for further manipulation.
+    ///
+    /// This function is useful when creating values that must contain their
+    /// key. The returned VacantEntry reserves a slot in the arena and is able
+    /// to query the associated key.
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T, N, V> {
+        #[cold]
+        #[inline(never)]
+        fn allocate_vacant_slot<T, const N: usize, V: Version>(this: &mut Arena<T, N, V>) {
+            this.on_exhaustion.check(this.slots.as_slice().len(), this.num_elements);
+            this.next = this.slots.as_slice().len();
+            this.slots.push(Slot {
+                version: V::EMPTY,
+                data: Data {
+                    next: this.next.wrapping_add(1),
+                },
+            });
+        }
+
+        if self.slots.as_slice().len() == self.next {
+            allocate_vacant_slot(self);
+        }
+
+        let slot = unsafe { self.slots.as_slice().get_unchecked(self.next) };
+
+        VacantEntry {
+            new_next: unsafe { slot.data.next },
+            arena: self,
+        }
+    }
+
+    /// Insert a value in the arena, returning key assigned to the value.
+    ///
+    /// The returned key can later be used to retrieve or remove the value
+    /// using indexed lookup and remove. The first `N` slots don't allocate.
+    pub fn insert<K: BuildArenaKey<(), V>>(&mut self, value: T) -> K { self.vacant_entry().insert(value) }
+
+    /// Insert a value in the arena, returning key assigned to the value
+    /// and a unique reference to the inserted value.
+    pub fn insert_mut<K: BuildArenaKey<(), V>>(&mut self, value: T) -> (K, &mut T) {
+        self.vacant_entry().insert_mut(value)
+    }
+
+    /// Return true if a value is associated with the given key.
+    pub fn contains<K: ArenaKey<(), V>>(&self, key: K) -> bool { self.find(&key).is_some() }
+
+    fn find<K: ArenaKey<(), V>>(&self, key: &K) -> Option<usize> {
+        let version = self.slots.as_slice().get(key.index())?.version;
+
+        let is_associated = match key.version() {
+            Some(saved) => version.equals_saved(saved),
+            None => version.is_full(),
+        };
+
+        if is_associated {
+            Some(key.index())
+        } else {
+            None
+        }
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// Panics if key is not associated with a value.
+    #[track_caller]
+    pub fn remove<K: ArenaKey<(), V>>(&mut self, key: K) -> T {
+        self.try_remove(key)
+            .expect("Could not remove from an `Arena` using a stale `Key`")
+    }
+
+    /// Remove and return the value associated with the given key.
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn try_remove<K: ArenaKey<(), V>>(&mut self, key: K) -> Option<T> {
+        let index = self.find(&key)?;
+        self.num_elements -= 1;
+        let slot = unsafe { self.slots.as_mut_slice().get_unchecked_mut(index) };
+        Some(unsafe { slot.remove_unchecked(index, &mut self.next, &mut self.exhausted) })
+    }
+
+    /// Removes the value associated with the given key.
+    ///
+    /// Returns true if the value was removed, an false otherwise
+    pub fn delete<K: ArenaKey<(), V>>(&mut self, key: K) -> bool {
+        match self.find(&key) {
+            Some(index) => {
+                self.num_elements -= 1;
+                let slot = unsafe { self.slots.as_mut_slice().get_unchecked_mut(index) };
+                unsafe { slot.delete_unchecked(index, &mut self.next, &mut self.exhausted) }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return a shared reference to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then None is returned.
+    pub fn get<K: ArenaKey<(), V>>(&self, key: K) -> Option<&T> {
+        let index = self.find(&key)?;
+        unsafe { Some(&*self.slots.as_slice().get_unchecked(index).data.value) }
+    }
+
+    /// Return a unique reference to the value associated with the given key.
+    ///
+    /// If the given key is not associated with a value, then None is returned.
+    pub fn get_mut<K: ArenaKey<(), V>>(&mut self, key: K) -> Option<&mut T> {
+        let index = self.find(&key)?;
+        unsafe { Some(&mut *self.slots.as_mut_slice().get_unchecked_mut(index).data.value) }
+    }
+
+    /// An iterator over the keys of the arena, in no particular order
+    pub fn keys<K: BuildArenaKey<(), V>>(&self) -> Keys<'_, T, (), V, K> {
+        Keys {
+            entries: self.entries(),
+        }
+    }
+
+    /// An iterator of shared references to values of the arena,
+    /// in no particular order
+    pub fn iter(&self) -> Iter<'_, T, V> {
+        Iter {
+            slots: Occupied {
+                slots: self.slots.as_slice().iter(),
+            },
+        }
+    }
+
+    /// An iterator of unique references to values of the arena,
+    /// in no particular order
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, V> {
+        IterMut {
+            slots: Occupied {
+                slots: self.slots.as_mut_slice().iter_mut(),
+            },
+        }
+    }
+
+    /// An iterator of keys and shared references to values of the arena,
+    /// in no particular order
+    pub fn entries<K: BuildArenaKey<(), V>>(&self) -> Entries<'_, T, (), V, K> {
+        Entries {
+            slots: Occupied {
+                slots: self.slots.as_slice().iter().enumerate(),
+            },
+            ident: &(),
+            key: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize, V: Version, K: ArenaKey<(), V>> Index<K> for Arena<T, N, V> {
+    type Output = T;
+
+    #[track_caller]
+    fn index(&self, key: K) -> &Self::Output { self.get(key).expect("Tried to access `Arena` with a stale `Key`") }
+}
+
+impl<T, const N: usize, V: Version, K: ArenaKey<(), V>> IndexMut<K> for Arena<T, N, V> {
+    #[track_caller]
+    fn index_mut(&mut self, key: K) -> &mut Self::Output {
+        self.get_mut(key).expect("Tried to access `Arena` with a stale `Key`")
+    }
+}
+
+impl<T: Clone, const N: usize, V: Version> Clone for Arena<T, N, V> {
+    fn clone(&self) -> Self {
+        let mut slots = Slots::INIT;
+        for slot in self.slots.as_slice() {
+            slots.push(slot.clone());
+        }
+
+        Self {
+            slots,
+            next: self.next,
+            num_elements: self.num_elements,
+            exhausted: self.exhausted,
+            on_exhaustion: self.on_exhaustion,
+        }
+    }
+}
+
+use core::fmt;
+
+impl<T: fmt::Debug, const N: usize, V: Version + fmt::Debug> fmt::Debug for Arena<T, N, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("slots", &self.slots.as_slice())
+            .field("next", &self.next)
+            .field("num_elements", &self.num_elements)
+            .field("exhausted", &self.exhausted)
+            .field("on_exhaustion", &self.on_exhaustion)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Arena;
+    use crate::Key;
+    use std::{rc::Rc, vec::Vec};
+
+    #[test]
+    fn spill() {
+        let mut arena = Arena::<_, 2>::new();
+        let a: usize = arena.insert(0);
+        let b: Key<usize> = arena.insert(1);
+        assert!(arena.is_inline());
+        assert_eq!(arena.capacity(), 2);
+
+        let c: Key<usize> = arena.insert(2);
+        assert!(!arena.is_inline());
+        assert_eq!(arena.len(), 3);
+        assert_eq!(arena[a], 0);
+        assert_eq!(arena[b], 1);
+        assert_eq!(arena[c], 2);
+
+        assert_eq!(arena.remove(b), 1);
+        assert!(!arena.contains(b));
+        let d: Key<usize> = arena.insert(3);
+        assert_eq!(*d.id(), 1);
+        assert!(!arena.contains(b));
+        assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 3, 2]);
+    }
+
+    #[test]
+    fn reuse_inline() {
+        let mut arena = Arena::<_, 1>::new();
+        for i in 0..10 {
+            let key: Key<usize> = arena.insert(i);
+            assert_eq!(arena.remove(key), i);
+        }
+        assert!(arena.is_inline());
+    }
+
+    #[test]
+    fn zero_inline() {
+        let mut arena = Arena::<_, 0>::new();
+        let a: usize = arena.insert(0);
+        assert!(!arena.is_inline());
+        assert_eq!(arena.get(a), Some(&0));
+    }
+
+    #[test]
+    fn drops() {
+        let rc = Rc::new(());
+        let mut arena = Arena::<_, 2>::new();
+        let a: usize = arena.insert(rc.clone());
+        let _: usize = arena.insert(rc.clone());
+        assert!(arena.delete(a));
+        assert_eq!(Rc::strong_count(&rc), 2);
+        let clone = arena.clone();
+        assert_eq!(Rc::strong_count(&rc), 3);
+        drop(clone);
+        arena.clear();
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        for _ in 0..4 {
+            let _: usize = arena.insert(rc.clone());
+        }
+        drop(arena);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -13,32 +13,49 @@
 use core::{
     marker::PhantomData,
     mem::{replace, ManuallyDrop},
-    ops::{Index, IndexMut},
+    ops::{ControlFlow, Index, IndexMut},
+    ptr,
 };
 
 use pui_vec::PuiVec;
+use std::collections::TryReserveError;
 
 use crate::{
-    version::{DefaultVersion, Version},
-    ArenaKey, BuildArenaKey,
+    version::{DefaultVersion, DynVersion, ExhaustionPolicy, Tagged, Version},
+    ArenaKey, BuildArenaKey, TrustedKey,
 };
 
-union Data<T> {
-    value: ManuallyDrop<T>,
-    next: usize,
+mod meta;
+pub use meta::MetaArena;
+
+pub(super) union Data<T> {
+    pub(super) value: ManuallyDrop<T>,
+    pub(super) next: usize,
 }
 
-struct Slot<T, V: Version> {
-    version: V,
-    data: Data<T>,
+pub(super) struct Slot<T, V: Version> {
+    pub(super) version: V,
+    pub(super) data: Data<T>,
 }
 
 /// A sparse arena
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Arena<T, I = (), V: Version = DefaultVersion> {
     slots: PuiVec<Slot<T, V>, I>,
     next: usize,
     num_elements: usize,
+    exhausted: usize,
+    on_exhaustion: ExhaustionPolicy,
+}
+
+/// The structure of a sparse arena, without any of it's values
+///
+/// Created by [`Arena::structure_snapshot`], and can be restored with [`Arena::restore_structure`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Structure<V> {
+    // the version of each slot, and it's link in the free list if it's vacant
+    slots: std::vec::Vec<(V, usize)>,
+    next: usize,
 }
 
 /// An empty slot in a sparse arena
@@ -56,7 +73,7 @@ impl<T, V: Version> Drop for Slot<T, V> {
 }
 
 impl<T, V: Version> Slot<T, V> {
-    unsafe fn remove_unchecked(&mut self, index: usize, next: &mut usize) -> T {
+    pub(super) unsafe fn remove_unchecked(&mut self, index: usize, next: &mut usize, exhausted: &mut usize) -> T {
         let value = ManuallyDrop::take(&mut self.data.value);
         match self.version.mark_empty() {
             Ok(next_version) => {
@@ -66,20 +83,26 @@ impl<T, V: Version> Slot<T, V> {
                     next: replace(next, index),
                 };
             }
-            Err(next_version) => self.version = next_version,
+            Err(next_version) => {
+                self.version = next_version;
+                *exhausted += 1;
+            }
         }
 
         value
     }
 
-    unsafe fn delete_unchecked(&mut self, index: usize, next: &mut usize) {
-        struct Fixup<'a, T, V: Version>(&'a mut Slot<T, V>, usize, &'a mut usize);
+    pub(super) unsafe fn delete_unchecked(&mut self, index: usize, next: &mut usize, exhausted: &mut usize) {
+        struct Fixup<'a, T, V: Version>(&'a mut Slot<T, V>, usize, &'a mut usize, &'a mut usize);
 
         impl<T, V: Version> Drop for Fixup<'_, T, V> {
             fn drop(&mut self) {
-                let Self(ref mut slot, index, ref mut next) = *self;
+                let Self(ref mut slot, index, ref mut next, ref mut exhausted) = *self;
                 match unsafe { slot.version.mark_empty() } {
-                    Err(next_version) => slot.version = next_version,
+                    Err(next_version) => {
+                        slot.version = next_version;
+                        **exhausted += 1;
+                    }
                     Ok(next_version) => {
                         slot.version = next_version;
 
@@ -91,14 +114,46 @@ impl<T, V: Version> Slot<T, V> {
             }
         }
 
-        let fixup = Fixup(self, index, next);
+        let fixup = Fixup(self, index, next, exhausted);
 
         ManuallyDrop::drop(&mut fixup.0.data.value);
     }
 }
 
-impl<T> Default for Arena<T> {
-    fn default() -> Self { Self::new() }
+impl<T, I: Default, V: Version> Default for Arena<T, I, V> {
+    fn default() -> Self { Self::with_ident(I::default()) }
+}
+
+impl<T: Clone, I: Clone, V: Version> Clone for Arena<T, I, V> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            next: self.next,
+            num_elements: self.num_elements,
+            exhausted: self.exhausted,
+            on_exhaustion: self.on_exhaustion,
+        }
+    }
+
+    /// Clones `source` into this arena, reusing it's allocation and the allocations of it's values
+    ///
+    /// If cloning a value panics, this arena is left with a mix of it's old values and `source`'s values
+    fn clone_from(&mut self, source: &Self) {
+        struct Relink<'a, T, I, V: Version>(&'a mut Arena<T, I, V>);
+
+        impl<T, I, V: Version> Drop for Relink<'_, T, I, V> {
+            fn drop(&mut self) { self.0.relink() }
+        }
+
+        let relink = Relink(self);
+        relink.0.slots.clone_from(&source.slots);
+        core::mem::forget(relink);
+
+        self.next = source.next;
+        self.num_elements = source.num_elements;
+        self.exhausted = source.exhausted;
+        self.on_exhaustion = source.on_exhaustion;
+    }
 }
 
 impl<T> Arena<T> {
@@ -112,16 +167,52 @@ impl<T, V: Version> Arena<T, (), V> {
         slots: PuiVec::new(()),
         next: 0,
         num_elements: 0,
+        exhausted: 0,
+        on_exhaustion: ExhaustionPolicy::Retire,
     };
 
     /// Clear the arena without reducing it's capacity
     pub fn clear(&mut self) {
         self.next = 0;
+        self.num_elements = 0;
+        self.exhausted = 0;
         self.slots.vec_mut().clear();
     }
+
+    /// Create a new arena by mapping each value in this arena
+    ///
+    /// Every new value is at the same index and version as the value it was
+    /// created from, so keys for this arena can also be used with the new arena.
+    pub fn map_values<U, F: FnMut(&T) -> U>(&self, mut f: F) -> Arena<U, (), V> {
+        let slots = self
+            .slots
+            .iter()
+            .map(|slot| {
+                let version = slot.version;
+                let data = if version.is_full() {
+                    Data {
+                        value: ManuallyDrop::new(f(unsafe { &*slot.data.value })),
+                    }
+                } else {
+                    Data {
+                        next: unsafe { slot.data.next },
+                    }
+                };
+                Slot { version, data }
+            })
+            .collect();
+
+        Arena {
+            slots: PuiVec::from_raw_parts(slots, ()),
+            next: self.next,
+            num_elements: self.num_elements,
+            exhausted: self.exhausted,
+            on_exhaustion: self.on_exhaustion,
+        }
+    }
 }
 
-impl<T, I, V: Version> VacantEntry<'_, T, I, V> {
+impl<'a, T, I, V: Version> VacantEntry<'a, T, I, V> {
     /// Get the key associated with the `VacantEntry`, this key can be used
     /// once this `VacantEntry` gets filled
     pub fn key<K: BuildArenaKey<I, V>>(&self) -> K {
@@ -140,28 +231,135 @@ impl<T, I, V: Version> VacantEntry<'_, T, I, V> {
     }
 
     /// Insert an element into the vacant entry
-    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K {
-        let slot = unsafe { self.arena.slots.get_unchecked_mut(self.arena.next) };
+    pub fn insert<K: BuildArenaKey<I, V>>(self, value: T) -> K { self.insert_mut(value).0 }
+
+    /// Insert an element into the vacant entry, and return a unique reference to it
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(self, value: T) -> (K, &'a mut T) {
+        let arena = self.arena;
+        let index = arena.next;
+        let slot = unsafe { arena.slots.get_unchecked_mut(index) };
         slot.data = Data {
             value: ManuallyDrop::new(value),
         };
         slot.version = unsafe { slot.version.mark_full() };
         let version = unsafe { slot.version.save() };
-        let index = self.arena.next;
-        self.arena.next = self.new_next;
-        self.arena.num_elements += 1;
+        arena.next = self.new_next;
+        arena.num_elements += 1;
+
+        let key = unsafe { K::new_unchecked(index, version, arena.ident()) };
+        let value = unsafe { &mut *arena.slots.get_unchecked_mut(index).data.value };
+        (key, value)
+    }
+}
+
+impl<T, I, V: Version, const BITS: u32> VacantEntry<'_, T, I, Tagged<V, BITS>> {
+    pub(crate) fn set_tag(&mut self, tag: u32) {
+        let slot = unsafe { self.arena.slots.get_unchecked_mut(self.arena.next) };
+        slot.version = slot.version.with_tag(tag);
+    }
+
+    /// Insert an element into the vacant entry with the given tag
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(mut self, tag: u32, value: T) -> K {
+        self.set_tag(tag);
+        self.insert(value)
+    }
+}
 
-        unsafe { K::new_unchecked(index, version, self.arena.ident()) }
+impl<T, I, V: Version, const BITS: u32> Arena<T, I, Tagged<V, BITS>> {
+    /// Insert a value in the arena with the given tag, returning key assigned to the value.
+    ///
+    /// Panics if `tag` doesn't fit in `BITS` bits
+    pub fn insert_tagged<K: BuildArenaKey<I, Tagged<V, BITS>>>(&mut self, tag: u32, value: T) -> K {
+        self.vacant_entry().insert_tagged(tag, value)
     }
 }
 
 impl<T, I, V: Version> Arena<T, I, V> {
+    /// Rebuild the list of empty slots and the element counts from the versions of the slots
+    fn relink(&mut self) {
+        let mut next = self.slots.len();
+        self.num_elements = 0;
+        self.exhausted = 0;
+
+        for index in (0..self.slots.len()).rev() {
+            let slot = unsafe { self.slots.get_unchecked_mut(index) };
+            if slot.version.is_full() {
+                self.num_elements += 1;
+            } else if slot.version.is_exhausted() {
+                self.exhausted += 1;
+            } else {
+                slot.data = Data { next };
+                next = index;
+            }
+        }
+
+        self.next = next;
+    }
+
+    /// Check the internal invariants of the arena, this is intended for tests and fuzzing
+    ///
+    /// # Panics
+    ///
+    /// If the free list contains an occupied or exhausted slot, or has a cycle,
+    /// or if `len`/`exhausted` don't match the slots
+    #[cfg(feature = "validate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "validate")))]
+    pub fn check_invariants(&self) {
+        let slots = &self.slots[..];
+        let mut vacant = 0;
+        let mut next = self.next;
+
+        while next != slots.len() {
+            let slot = match slots.get(next) {
+                Some(slot) => slot,
+                None => panic!("free list link {} is out of bounds (len: {})", next, slots.len()),
+            };
+            assert!(!slot.version.is_full(), "occupied slot {} is on the free list", next);
+            assert!(!slot.version.is_exhausted(), "exhausted slot {} is on the free list", next);
+            vacant += 1;
+            assert!(vacant <= slots.len(), "the free list has a cycle");
+            next = unsafe { slot.data.next };
+        }
+
+        let occupied = slots.iter().filter(|slot| slot.version.is_full()).count();
+        let exhausted = slots.iter().filter(|slot| slot.version.is_exhausted()).count();
+
+        assert_eq!(self.num_elements, occupied, "`len` doesn't match the number of occupied slots");
+        assert_eq!(self.exhausted, exhausted, "`exhausted` doesn't match the number of exhausted slots");
+        assert_eq!(
+            vacant,
+            slots.len() - occupied - exhausted,
+            "not every vacant slot is on the free list"
+        );
+    }
+
+    /// Overwrite the version of an occupied slot, this allows testing version exhaustion
+    /// without inserting and removing values from a slot until it's exhausted
+    ///
+    /// # Panics
+    ///
+    /// If the slot at `index` isn't occupied
+    #[cfg(feature = "test-util")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    pub fn force_version(&mut self, index: usize, save: V::Save)
+    where
+        V: crate::version::RestorableVersion,
+    {
+        let slot = &mut self.slots[index];
+        assert!(slot.version.is_full(), "tried to force the version of the vacant slot {}", index);
+        slot.version = V::from_saved(save);
+    }
+
     /// Create a new arena with the given identifier
     pub fn with_ident(ident: I) -> Self {
         Self {
             slots: PuiVec::new(ident),
             next: 0,
             num_elements: 0,
+            exhausted: 0,
+            on_exhaustion: ExhaustionPolicy::Retire,
         }
     }
 
@@ -174,6 +372,29 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Returns the number of elements in this arena
     pub fn len(&self) -> usize { self.num_elements }
 
+    /// Returns the number of slots that have exhausted their versions
+    ///
+    /// These slots will never be reused, see [`Version`] for details.
+    pub fn exhausted_slots(&self) -> usize { self.exhausted }
+
+    /// An iterator over the indices of the vacant slots that can be reused
+    ///
+    /// The indices are yielded in the order of the internal list of empty slots,
+    /// which is the order that they would be filled by `insert`. Exhausted slots
+    /// are never yielded.
+    pub fn free_slots(&self) -> FreeSlots<'_, T, V> {
+        FreeSlots {
+            slots: &self.slots,
+            next: self.next,
+        }
+    }
+
+    /// Returns what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn on_exhaustion(&self) -> ExhaustionPolicy { self.on_exhaustion }
+
+    /// Set what this arena does when it needs to grow, but some of it's slots are exhausted
+    pub fn set_on_exhaustion(&mut self, policy: ExhaustionPolicy) { self.on_exhaustion = policy; }
+
     /// Returns the capacity of this arena
     pub fn capacity(&self) -> usize { self.slots.capacity() }
 
@@ -182,11 +403,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// frequent reallocations. After calling reserve, capacity will be greater
     /// than or equal to `self.len() + additional`. Does nothing if capacity is
     /// already sufficient.
-    pub fn reserve(&mut self, additional: usize) {
-        if let Some(additional) = self.capacity().wrapping_sub(self.num_elements).checked_sub(additional) {
-            self.slots.reserve(additional)
-        }
-    }
+    pub fn reserve(&mut self, additional: usize) { self.slots.reserve(additional) }
 
     /// Reserves the minimum capacity for exactly additional more elements
     /// to be inserted in the given collection. After calling reserve_exact,
@@ -196,9 +413,34 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// Note that the allocator may give the collection more space than it
     /// requests. Therefore, capacity can not be relied upon to be precisely
     /// minimal. Prefer reserve if future insertions are expected.
-    pub fn reserve_exact(&mut self, additional: usize) {
-        if let Some(additional) = self.capacity().wrapping_sub(self.num_elements).checked_sub(additional) {
-            self.slots.reserve_exact(additional)
+    pub fn reserve_exact(&mut self, additional: usize) { self.slots.reserve_exact(additional) }
+
+    /// Tries to reserve capacity for at least additional more elements to be inserted
+    /// in the given collection. If this returns an error, the arena is unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.slots.try_reserve(additional)
+    }
+
+    /// Clone the arena, and it's capacity
+    ///
+    /// `Clone` already preserves the layout of the slots and the list of empty slots,
+    /// so the clone will produce the same keys as `self` under the same operations.
+    /// However, it may not have the same capacity as `self`. `clone_exact` allocates
+    /// the clone with at least the same capacity as `self`, in a single allocation.
+    pub fn clone_exact(&self) -> Self
+    where
+        T: Clone,
+        I: Clone,
+    {
+        let mut slots = std::vec::Vec::with_capacity(self.slots.capacity());
+        slots.extend_from_slice(&self.slots);
+
+        Self {
+            slots: PuiVec::from_raw_parts(slots, self.slots.ident().clone()),
+            next: self.next,
+            num_elements: self.num_elements,
+            exhausted: self.exhausted,
+            on_exhaustion: self.on_exhaustion,
         }
     }
 
@@ -222,6 +464,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
         #[cold]
         #[inline(never)]
         pub fn allocate_vacant_slot<T, I, V: Version>(this: &mut Arena<T, I, V>) {
+            this.on_exhaustion.check(this.slots.len(), this.num_elements);
             this.next = this.slots.len();
             let _: usize = this.slots.push(Slot {
                 version: V::EMPTY,
@@ -243,6 +486,55 @@ impl<T, I, V: Version> Arena<T, I, V> {
         }
     }
 
+    /// Return a handle to the vacant entry at exactly `index`
+    ///
+    /// If `index` is past the end of the arena, then the arena is grown with vacant slots
+    /// until `index` is in bounds. Returns `None` if the slot at `index` is occupied,
+    /// or if it's version is exhausted.
+    ///
+    /// This is useful for deserializing arenas with a fixed layout.
+    pub fn vacant_entry_at(&mut self, index: usize) -> Option<VacantEntry<'_, T, I, V>> {
+        for next in self.slots.len()..=index {
+            let _: usize = self.slots.push(Slot {
+                version: V::EMPTY,
+                data: Data {
+                    next: next.wrapping_add(1),
+                },
+            });
+        }
+
+        let version = self.slots[index].version;
+        if version.is_full() || version.is_exhausted() {
+            return None
+        }
+
+        // find the slot before `index` in the list of empty slots
+        let mut prev = None;
+        let mut current = self.next;
+
+        while current != index {
+            prev = Some(current);
+            current = unsafe { self.slots.get(current)?.data.next };
+        }
+
+        // then move `index` to the front of the list of empty slots
+        unsafe {
+            let next = self.slots.get_unchecked(index).data.next;
+            match prev {
+                Some(prev) => self.slots.get_unchecked_mut(prev).data.next = next,
+                None => self.next = next,
+            }
+
+            self.slots.get_unchecked_mut(index).data.next = self.next;
+            self.next = index;
+
+            Some(VacantEntry {
+                new_next: self.slots.get_unchecked(index).data.next,
+                arena: self,
+            })
+        }
+    }
+
     /// Insert a value in the arena, returning key assigned to the value.
     ///
     /// The returned key can later be used to retrieve or remove the value
@@ -250,18 +542,72 @@ impl<T, I, V: Version> Arena<T, I, V> {
     /// if needed.
     pub fn insert<K: BuildArenaKey<I, V>>(&mut self, value: T) -> K { self.vacant_entry().insert(value) }
 
+    /// Insert a value in the arena, returning key assigned to the value
+    /// and a unique reference to the inserted value.
+    ///
+    /// This avoids looking up the value again after inserting it.
+    pub fn insert_mut<K: BuildArenaKey<I, V>>(&mut self, value: T) -> (K, &mut T) {
+        self.vacant_entry().insert_mut(value)
+    }
+
     /// Return true if a value is associated with the given key.
-    pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool {
+    #[inline]
+    pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool { self.find(&key).is_some() }
+
+    /// Return whether each of the given keys is associated with a value
+    ///
+    /// The results are in the same order as the keys
+    pub fn contains_all<K: ArenaKey<I, V>>(&self, keys: &[K]) -> std::vec::Vec<bool> {
+        keys.iter().map(|key| self.contains(key)).collect()
+    }
+
+    /// Validate the key, and return it's index if it's associated with a value
+    #[inline]
+    fn find<K: ArenaKey<I, V>>(&self, key: &K) -> Option<usize> {
         let is_index_guarnateed_valid = key.validate_ident(self.ident(), crate::Validator::new()).into_inner();
         let index = key.index();
         if !is_index_guarnateed_valid && self.slots.len() <= index {
-            return false
+            return None
         }
+
         let version = unsafe { self.slots.get_unchecked(index).version };
 
-        match key.version() {
+        let is_associated = match key.version() {
             Some(saved) => version.equals_saved(saved),
             None => version.is_full(),
+        };
+
+        if is_associated {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Classify the given key, explaining why it is or isn't associated with a value
+    ///
+    /// This returns [`KeyStatus::Live`](crate::KeyStatus::Live) if and only if `contains` would return true
+    pub fn classify<K: ArenaKey<I, V>>(&self, key: K) -> crate::KeyStatus {
+        use crate::KeyStatus;
+
+        if !key.is_owned_by(self.ident()) {
+            return KeyStatus::WrongIdent
+        }
+
+        let index = key.index();
+        if self.slots.len() <= index {
+            return KeyStatus::OutOfBounds
+        }
+
+        let version = unsafe { self.slots.get_unchecked(index).version };
+
+        if version.is_empty() {
+            return KeyStatus::Vacant
+        }
+
+        match key.version() {
+            Some(saved) if !version.equals_saved(saved) => KeyStatus::StaleVersion,
+            _ => KeyStatus::Live,
         }
     }
 
@@ -284,19 +630,34 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// Returns `None` if key is not associated with a value.
     pub fn try_remove<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<T> {
-        if self.contains(&key) {
-            let index = key.index();
-            Some(unsafe { self.remove_unchecked(index) })
-        } else {
+        let index = self.find(&key)?;
+        Some(unsafe { self.remove_unchecked(index) })
+    }
+
+    /// Remove and return the value associated with the given key, along with
+    /// the saved version that the next value inserted into it's slot will have
+    ///
+    /// Keys for this slot with an older version are stale. The version is `None`
+    /// if the slot's version is exhausted, so it will never be reused.
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn remove_versioned<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<(T, Option<V::Save>)> {
+        let index = self.find(&key)?;
+        let value = unsafe { self.remove_unchecked(index) };
+        let version = unsafe { self.slots.get_unchecked(index).version };
+        let version = if version.is_exhausted() {
             None
-        }
+        } else {
+            Some(unsafe { version.mark_full().save() })
+        };
+        Some((value, version))
     }
 
     unsafe fn remove_unchecked(&mut self, index: usize) -> T {
         self.num_elements -= 1;
         self.slots
             .get_unchecked_mut(index)
-            .remove_unchecked(index, &mut self.next)
+            .remove_unchecked(index, &mut self.next, &mut self.exhausted)
     }
 
     /// Removes the value associated with the given key.
@@ -306,13 +667,12 @@ impl<T, I, V: Version> Arena<T, I, V> {
     ///
     /// Returns true if the value was removed, an false otherwise
     pub fn delete<K: ArenaKey<I, V>>(&mut self, key: K) -> bool {
-        if self.contains(&key) {
-            unsafe {
-                self.delete_unchecked(key.index());
+        match self.find(&key) {
+            Some(index) => {
+                unsafe { self.delete_unchecked(index) }
                 true
             }
-        } else {
-            false
+            None => false,
         }
     }
 
@@ -320,26 +680,94 @@ impl<T, I, V: Version> Arena<T, I, V> {
         self.num_elements -= 1;
         self.slots
             .get_unchecked_mut(index)
-            .delete_unchecked(index, &mut self.next)
+            .delete_unchecked(index, &mut self.next, &mut self.exhausted)
     }
 
     /// Return a shared reference to the value associated with the given key.
     ///
     /// If the given key is not associated with a value, then None is returned.
+    #[inline]
     pub fn get<K: ArenaKey<I, V>>(&self, key: K) -> Option<&T> {
-        if self.contains(&key) {
-            unsafe { Some(self.get_unchecked(key.index())) }
-        } else {
-            None
+        let index = self.find(&key)?;
+        unsafe { Some(self.get_unchecked(index)) }
+    }
+
+    /// Return a shared reference to the value associated with each of the given keys,
+    /// or `None` for each key that isn't associated with a value
+    ///
+    /// The results are yielded in the same order as the keys
+    pub fn get_batch<'a, K: ArenaKey<I, V> + 'a, Iter: IntoIterator<Item = K>>(
+        &'a self,
+        keys: Iter,
+    ) -> impl Iterator<Item = Option<&'a T>> {
+        keys.into_iter().map(move |key| self.get(key))
+    }
+
+    /// Return a clone of the value associated with the given key,
+    /// or `T::default()` if the key isn't associated with a value
+    pub fn get_or_default<K: ArenaKey<I, V>>(&self, key: K) -> T
+    where
+        T: Default + Clone,
+    {
+        match self.get(key) {
+            Some(value) => value.clone(),
+            None => T::default(),
         }
     }
 
     /// Return a unique reference to the value associated with the given key.
     ///
     /// If the given key is not associated with a value, then None is returned.
+    ///
+    /// The version of the key is checked here, and the returned reference keeps the
+    /// arena borrowed, so the slot can't be removed or reused while the reference is alive.
+    ///
+    /// ```rust,compile_fail
+    /// # use pui_arena::{base::sparse::Arena, Key};
+    /// let mut arena = Arena::new();
+    /// let a: Key<usize, _> = arena.insert(0);
+    /// let value = arena.get_mut(a).unwrap();
+    /// arena.remove(a);
+    /// let b: Key<usize, _> = arena.insert(1);
+    /// *value = 2;
+    /// ```
+    #[inline]
     pub fn get_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<&mut T> {
-        if self.contains(&key) {
-            unsafe { Some(self.get_unchecked_mut(key.index())) }
+        let index = self.find(&key)?;
+        unsafe { Some(self.get_unchecked_mut(index)) }
+    }
+
+    /// Return unique references to the values associated with each of the given keys.
+    ///
+    /// If any of the given keys is not associated with a value, or if any two keys
+    /// are associated with the same value, then None is returned.
+    pub fn get_disjoint_mut<K: ArenaKey<I, V>, const N: usize>(&mut self, keys: [K; N]) -> Option<[&mut T; N]> {
+        let mut indices = [0; N];
+
+        for (i, key) in keys.iter().enumerate() {
+            let index = self.find(key)?;
+
+            if indices[..i].contains(&index) {
+                return None
+            }
+
+            indices[i] = index;
+        }
+
+        let slots = self.slots.as_mut_ptr();
+        Some(indices.map(|index| unsafe { &mut *(*slots.add(index)).data.value }))
+    }
+
+    /// Return the key associated with a reference to a value in the arena
+    ///
+    /// If `value` doesn't point to a value in the arena, then None is returned.
+    pub fn key_of<K: BuildArenaKey<I, V>>(&self, value: &T) -> Option<K> {
+        let offset = (value as *const T as usize).checked_sub(self.slots.as_ptr() as usize)?;
+        let index = offset / core::mem::size_of::<Slot<T, V>>();
+        let slot = self.slots.get(index)?;
+
+        if slot.version.is_full() && ptr::eq(unsafe { &*slot.data.value }, value) {
+            self.parse_key(index)
         } else {
             None
         }
@@ -365,9 +793,225 @@ impl<T, I, V: Version> Arena<T, I, V> {
         &mut *self.slots.get_unchecked_mut(index).data.value
     }
 
+    /// Return unique references to the values associated with the two
+    /// given indices without performing bounds checking, or checks
+    /// if there are values associated to the indices
+    ///
+    /// # Safety
+    ///
+    /// `i != j`, and `contains` should return true with both indices.
+    pub unsafe fn get_unchecked_mut2(&mut self, i: usize, j: usize) -> (&mut T, &mut T) {
+        debug_assert_ne!(i, j);
+        let slots = self.slots.as_mut_ptr();
+        (&mut *(*slots.add(i)).data.value, &mut *(*slots.add(j)).data.value)
+    }
+
+    /// Return a shared reference to the value associated with the given key
+    /// without checking it's identifier, bounds, or version
+    ///
+    /// # Safety
+    ///
+    /// `contains` should return true with the given key.
+    pub unsafe fn get_unchecked_key<K: ArenaKey<I, V>>(&self, key: &K) -> &T { self.get_unchecked(key.index()) }
+
+    /// Return a unique reference to the value associated with the given key
+    /// without checking it's identifier, bounds, or version
+    ///
+    /// # Safety
+    ///
+    /// `contains` should return true with the given key.
+    pub unsafe fn get_unchecked_key_mut<K: ArenaKey<I, V>>(&mut self, key: &K) -> &mut T {
+        self.get_unchecked_mut(key.index())
+    }
+
+    /// Call the given closure with a view of this arena that can validate keys once,
+    /// and then access their values without any checks
+    ///
+    /// Each call produces a fresh brand, so a [`TrustedKey`] can't be used
+    /// with another view, or after the closure returns.
+    ///
+    /// ```rust
+    /// # use pui_arena::base::sparse::Arena;
+    /// let mut arena = Arena::new();
+    /// let keys: Vec<pui_arena::Key<usize>> = (0..10).map(|i| arena.insert(i)).collect();
+    ///
+    /// let sum = arena.with_trusted_keys(|arena| {
+    ///     let trusted: Vec<_> = keys.iter().filter_map(|key| arena.validate_once(key)).collect();
+    ///     (0..3).map(|_| trusted.iter().map(|&key| arena.get(key)).sum::<i32>()).sum::<i32>()
+    /// });
+    ///
+    /// assert_eq!(sum, 135);
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use pui_arena::base::sparse::Arena;
+    /// let mut arena = Arena::new();
+    /// let key: usize = arena.insert(0);
+    ///
+    /// let trusted = arena.with_trusted_keys(|arena| arena.validate_once(key));
+    /// ```
+    pub fn with_trusted_keys<'a, R, F: for<'brand> FnOnce(Trusted<'brand, 'a, T, I, V>) -> R>(&'a self, f: F) -> R {
+        f(Trusted {
+            arena: self,
+            brand: PhantomData,
+        })
+    }
+
+    /// Call the given closure with a view of this arena that can validate keys once,
+    /// and then access their values without any checks
+    ///
+    /// Like [`Arena::with_trusted_keys`], but the view can also provide unique references
+    pub fn with_trusted_keys_mut<'a, R, F: for<'brand> FnOnce(TrustedMut<'brand, 'a, T, I, V>) -> R>(
+        &'a mut self,
+        f: F,
+    ) -> R {
+        f(TrustedMut {
+            arena: self,
+            brand: PhantomData,
+        })
+    }
+
     /// Deletes all elements from the arena
     pub fn delete_all(&mut self) { self.retain(|_| false) }
 
+    /// Deletes all elements from the arena, and resets the version of each slot
+    /// that isn't exhausted, without reducing it's capacity
+    ///
+    /// This allows slots to be reused for longer, but it gives up the protection from
+    /// the ABA problem for all keys created before this call. Those keys may
+    /// alias keys created after this call, and access unrelated values.
+    /// So this should only be used if no keys created before the reset are used after it.
+    pub fn drain_reset(&mut self) {
+        self.delete_all();
+
+        for slot in self.slots.iter_mut() {
+            if !slot.version.is_exhausted() {
+                slot.version = V::EMPTY;
+            }
+        }
+    }
+
+    /// Returns an object safe view of the version of the slot at `index`,
+    /// or `None` if `index` is out of bounds
+    ///
+    /// This is intended for tools that inspect arenas, see [`DynVersion`] for details
+    pub fn dyn_version(&self, index: usize) -> Option<std::boxed::Box<dyn DynVersion>>
+    where
+        V: 'static,
+        V::Save: 'static,
+    {
+        self.slots.get(index).map(|slot| std::boxed::Box::new(slot.version) as _)
+    }
+
+    /// Check if the slot at `index` was reused since it had the version `old`
+    ///
+    /// Unlike [`Arena::contains`], this is also true if the slot is currently vacant,
+    /// but the value with the version `old` was removed. It's false if `old`
+    /// is the version the next value inserted in the slot will get.
+    /// If `index` is out of bounds, this returns true.
+    ///
+    /// This compares versions for equality, so it may return false if the slot's
+    /// version started over at `old` after a `drain_reset` or `restore_structure`.
+    ///
+    /// ```rust
+    /// # use pui_arena::{base::sparse::Arena, Key};
+    /// let mut arena = Arena::new();
+    /// let a: Key<usize> = arena.insert(0);
+    /// assert!(!arena.is_reused(*a.id(), *a.version()));
+    ///
+    /// arena.remove(a);
+    /// assert!(arena.is_reused(*a.id(), *a.version()));
+    /// ```
+    pub fn is_reused(&self, index: usize, old: V::Save) -> bool {
+        match self.slots.get(index) {
+            Some(slot) => crate::version::is_reused(slot.version, old),
+            None => true,
+        }
+    }
+
+    /// Save which slots are occupied, their versions, and the list of empty slots,
+    /// but not the values in the arena
+    pub fn structure_snapshot(&self) -> Structure<V> {
+        Structure {
+            slots: self
+                .slots
+                .iter()
+                .map(|slot| match slot.version.is_full() {
+                    true => (slot.version, 0),
+                    false => (slot.version, unsafe { slot.data.next }),
+                })
+                .collect(),
+            next: self.next,
+        }
+    }
+
+    /// Restore the versions and the list of empty slots saved by [`Arena::structure_snapshot`]
+    ///
+    /// Returns false and leaves the arena untouched if the slots that are occupied
+    /// don't match the slots that were occupied in the snapshot, because otherwise
+    /// values would be leaked or read from empty slots.
+    ///
+    /// Note: this rolls back the versions of the slots, so keys created after
+    /// the snapshot may be associated with new values (the ABA problem)
+    pub fn restore_structure(&mut self, structure: &Structure<V>) -> bool {
+        let current_len = self.slots.len();
+        let snapshot_len = structure.slots.len();
+
+        let is_occupied = |index: usize| matches!(self.slots.get(index), Some(slot) if slot.version.is_full());
+        let was_occupied = |index: usize| matches!(structure.slots.get(index), Some((version, _)) if version.is_full());
+
+        if (0..current_len.max(snapshot_len)).any(|index| is_occupied(index) != was_occupied(index)) {
+            return false
+        }
+
+        for (slot, &(version, next)) in self.slots.iter_mut().zip(&structure.slots) {
+            slot.version = version;
+
+            if version.is_empty() {
+                slot.data = Data { next };
+            }
+        }
+
+        for &(version, next) in structure.slots.iter().skip(current_len) {
+            let _: usize = self.slots.push(Slot {
+                version,
+                data: Data { next },
+            });
+        }
+
+        self.next = structure.next;
+        self.exhausted = self.slots.iter().filter(|slot| slot.version.is_exhausted()).count();
+
+        if current_len > snapshot_len {
+            // the snapshot's list of empty slots ends at `snapshot_len`, so it needs
+            // to be extended with all the slots that were added since the snapshot
+            let mut last = None;
+            let mut current = self.next;
+
+            while current != snapshot_len {
+                last = Some(current);
+                current = unsafe { self.slots.get_unchecked(current).data.next };
+            }
+
+            let mut next = current_len;
+
+            for index in (snapshot_len..current_len).rev() {
+                let slot = unsafe { self.slots.get_unchecked_mut(index) };
+                if !slot.version.is_exhausted() {
+                    slot.data = Data { next };
+                    next = index;
+                }
+            }
+
+            match last {
+                Some(last) => unsafe { self.slots.get_unchecked_mut(last).data.next = next },
+                None => self.next = next,
+            }
+        }
+
+        true
+    }
+
     /// Retain only the elements specified by the predicate.
     ///
     /// If the predicate returns for a given element true,
@@ -376,9 +1020,25 @@ impl<T, I, V: Version> Arena<T, I, V> {
         for i in 0..self.slots.len() {
             if let Some(value) = self.get_mut(unsafe { crate::TrustedIndex::new(i) }) {
                 if !f(value) {
-                    unsafe {
-                        self.slots.get_unchecked_mut(i).delete_unchecked(i, &mut self.next);
-                    }
+                    unsafe { self.delete_unchecked(i) }
+                }
+            }
+        }
+    }
+
+    /// Retain only the elements specified by the predicate, stopping early
+    /// once the predicate breaks.
+    ///
+    /// If the predicate returns `Continue(true)` for a given element,
+    /// then the element is kept in the arena. If it returns `Break(())`,
+    /// then that element and all remaining elements are kept.
+    pub fn retain_until<F: FnMut(&mut T) -> ControlFlow<(), bool>>(&mut self, mut f: F) {
+        for i in 0..self.slots.len() {
+            if let Some(value) = self.get_mut(unsafe { crate::TrustedIndex::new(i) }) {
+                match f(value) {
+                    ControlFlow::Break(()) => break,
+                    ControlFlow::Continue(true) => (),
+                    ControlFlow::Continue(false) => unsafe { self.delete_unchecked(i) },
                 }
             }
         }
@@ -423,6 +1083,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
             },
             next: &mut self.next,
             num_elements: &mut self.num_elements,
+            exhausted: &mut self.exhausted,
         }
     }
 
@@ -441,6 +1102,7 @@ impl<T, I, V: Version> Arena<T, I, V> {
             },
             next: &mut self.next,
             num_elements: &mut self.num_elements,
+            exhausted: &mut self.exhausted,
             filter,
             panicked: false,
         }
@@ -476,6 +1138,18 @@ impl<T, I, V: Version> Arena<T, I, V> {
         }
     }
 
+    /// An iterator of slot indices and shared references to values of the arena,
+    /// in no particular order
+    ///
+    /// This is the same as `entries::<usize>()`, which doesn't construct versioned keys
+    pub fn indexed(&self) -> Entries<'_, T, I, V, usize> { self.entries() }
+
+    /// An iterator of slot indices and unique references to values of the arena,
+    /// in no particular order
+    ///
+    /// This is the same as `entries_mut::<usize>()`, which doesn't construct versioned keys
+    pub fn indexed_mut(&mut self) -> EntriesMut<'_, T, I, V, usize> { self.entries_mut() }
+
     /// An iterator of keys and values of the arena,
     /// in no particular order, with each key being associated
     /// to the corrosponding value
@@ -490,6 +1164,45 @@ impl<T, I, V: Version> Arena<T, I, V> {
             key: PhantomData,
         }
     }
+
+    /// Consume the arena, mapping each key and value to a new value,
+    /// and return an arena of the new values
+    ///
+    /// Every value stays at the same index and version, so all keys
+    /// that were valid for this arena are valid for the new arena,
+    /// and stale keys remain stale.
+    pub fn drain_map<U, K: BuildArenaKey<I, V>, F: FnMut(K, T) -> U>(self, mut f: F) -> Arena<U, I, V> {
+        let (ident, slots) = unsafe { self.slots.into_raw_parts() };
+
+        let slots = slots
+            .into_iter()
+            .enumerate()
+            .map(|(index, slot)| {
+                let slot = ManuallyDrop::new(slot);
+                let version = slot.version;
+                let data = if version.is_full() {
+                    let value = unsafe { ptr::read(&*slot.data.value) };
+                    let key = unsafe { K::new_unchecked(index, version.save(), &ident) };
+                    Data {
+                        value: ManuallyDrop::new(f(key, value)),
+                    }
+                } else {
+                    Data {
+                        next: unsafe { slot.data.next },
+                    }
+                };
+                Slot { version, data }
+            })
+            .collect();
+
+        Arena {
+            slots: PuiVec::from_raw_parts(slots, ident),
+            next: self.next,
+            num_elements: self.num_elements,
+            exhausted: self.exhausted,
+            on_exhaustion: self.on_exhaustion,
+        }
+    }
 }
 
 impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
@@ -505,6 +1218,16 @@ impl<T, I, V: Version> IntoIterator for Arena<T, I, V> {
     }
 }
 
+#[cfg(feature = "pui-core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pui")))]
+impl<T, I: pui_core::OneShotIdentifier, V: Version> Arena<T, I, V> {
+    /// An iterator over the ids of the arena, in no particular order
+    ///
+    /// This is the same as `keys::<pui_vec::Id<_>>()`, these ids
+    /// elide bounds checks when they are used to access the arena
+    pub fn ids(&self) -> Keys<'_, T, I, V, pui_vec::Id<I::Token>> { self.keys() }
+}
+
 impl<T, I, V: Version, K: ArenaKey<I, V>> Index<K> for Arena<T, I, V> {
     type Output = T;
 
@@ -519,6 +1242,16 @@ impl<T, I, V: Version, K: ArenaKey<I, V>> IndexMut<K> for Arena<T, I, V> {
     }
 }
 
+/// Arenas are compared by the index and value of each occupied slot,
+/// versions and vacant slots are ignored
+impl<T: PartialEq, V: Version> PartialEq for Arena<T, (), V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.entries::<usize>().eq(other.entries::<usize>())
+    }
+}
+
+impl<T: Eq, V: Version> Eq for Arena<T, (), V> {}
+
 impl<T, I, V: Version> Extend<T> for Arena<T, I, V> {
     #[allow(clippy::drop_copy)]
     fn extend<Iter: IntoIterator<Item = T>>(&mut self, iter: Iter) {
@@ -574,8 +1307,9 @@ impl<T: fmt::Debug, V: Version + fmt::Debug> fmt::Debug for Slot<T, V> {
     }
 }
 
-struct Occupied<I> {
-    slots: I,
+#[derive(Clone)]
+pub(super) struct Occupied<I> {
+    pub(super) slots: I,
 }
 
 trait AsSlot {
@@ -633,9 +1367,122 @@ where
     fn next_back(&mut self) -> Option<Self::Item> { self.slots.by_ref().rfind(|slot| slot.as_slot().version.is_full()) }
 }
 
+/// A view of a sparse arena that can't remove values, created by [`Arena::with_trusted_keys`]
+pub struct Trusted<'brand, 'a, T, I, V: Version> {
+    arena: &'a Arena<T, I, V>,
+    brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<T, I, V: Version> Clone for Trusted<'_, '_, T, I, V> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<T, I, V: Version> Copy for Trusted<'_, '_, T, I, V> {}
+
+impl<'brand, 'a, T, I, V: Version> Trusted<'brand, 'a, T, I, V> {
+    /// The arena this view was created from
+    pub fn arena(&self) -> &'a Arena<T, I, V> { self.arena }
+
+    /// Validate the key, and if it's associated with a value, return a key that can be used without any checks
+    pub fn validate_once<K: ArenaKey<I, V>>(&self, key: K) -> Option<TrustedKey<'brand>> {
+        let index = self.arena.find(&key)?;
+        Some(unsafe { TrustedKey::new(index) })
+    }
+
+    /// Return a shared reference to the value associated with the given key, without any checks
+    #[inline]
+    pub fn get(&self, key: TrustedKey<'brand>) -> &'a T { unsafe { self.arena.get_unchecked(key.index()) } }
+
+    /// An iterator over the values in the arena, and keys that can be used without any checks
+    ///
+    /// ```rust
+    /// # use pui_arena::base::sparse::Arena;
+    /// let mut arena = Arena::new();
+    /// let _: usize = arena.insert(1);
+    /// let _: usize = arena.insert(2);
+    ///
+    /// let doubled = arena.with_trusted_keys(|arena| {
+    ///     arena.iter().map(|(key, &value)| value + arena.get(key)).collect::<Vec<_>>()
+    /// });
+    ///
+    /// assert_eq!(doubled, [2, 4]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (TrustedKey<'brand>, &'a T)> + Clone {
+        self.arena
+            .entries::<usize>()
+            .map(|(index, value)| (unsafe { TrustedKey::new(index) }, value))
+    }
+}
+
+/// A view of a sparse arena that can't remove values, created by [`Arena::with_trusted_keys_mut`]
+pub struct TrustedMut<'brand, 'a, T, I, V: Version> {
+    arena: &'a mut Arena<T, I, V>,
+    brand: PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl<'brand, T, I, V: Version> TrustedMut<'brand, '_, T, I, V> {
+    /// The arena this view was created from
+    pub fn arena(&self) -> &Arena<T, I, V> { self.arena }
+
+    /// Validate the key, and if it's associated with a value, return a key that can be used without any checks
+    pub fn validate_once<K: ArenaKey<I, V>>(&self, key: K) -> Option<TrustedKey<'brand>> {
+        let index = self.arena.find(&key)?;
+        Some(unsafe { TrustedKey::new(index) })
+    }
+
+    /// Return a shared reference to the value associated with the given key, without any checks
+    #[inline]
+    pub fn get(&self, key: TrustedKey<'brand>) -> &T { unsafe { self.arena.get_unchecked(key.index()) } }
+
+    /// Return a unique reference to the value associated with the given key, without any checks
+    #[inline]
+    pub fn get_mut(&mut self, key: TrustedKey<'brand>) -> &mut T {
+        unsafe { self.arena.get_unchecked_mut(key.index()) }
+    }
+
+    /// An iterator over keys to all values in the arena that can be used without any checks
+    pub fn keys(&self) -> impl Iterator<Item = TrustedKey<'brand>> + Clone + '_ {
+        self.arena.keys::<usize>().map(|index| unsafe { TrustedKey::new(index) })
+    }
+}
+
+/// Returned by [`Arena::free_slots`]
+pub struct FreeSlots<'a, T, V: Version> {
+    slots: &'a [Slot<T, V>],
+    next: usize,
+}
+
+impl<T, V: Version> Clone for FreeSlots<'_, T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots,
+            next: self.next,
+        }
+    }
+}
+
+impl<T, V: Version> Iterator for FreeSlots<'_, T, V> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next;
+        let slot = self.slots.get(index)?;
+        self.next = unsafe { slot.data.next };
+        Some(index)
+    }
+}
+
 /// Returned by [`Arena::keys`]
 pub struct Keys<'a, T, I, V: Version, K> {
-    entries: Entries<'a, T, I, V, K>,
+    pub(super) entries: Entries<'a, T, I, V, K>,
+}
+
+impl<T, I, V: Version, K> Clone for Keys<'_, T, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
 }
 
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Keys<'a, T, I, V, K> {
@@ -650,7 +1497,15 @@ impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> DoubleEndedIterator for Keys<
 
 /// Returned by [`Arena::iter`]
 pub struct Iter<'a, T, V: Version> {
-    slots: Occupied<core::slice::Iter<'a, Slot<T, V>>>,
+    pub(super) slots: Occupied<core::slice::Iter<'a, Slot<T, V>>>,
+}
+
+impl<T, V: Version> Clone for Iter<'_, T, V> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+        }
+    }
 }
 
 impl<'a, T, V: Version> Iterator for Iter<'a, T, V> {
@@ -665,7 +1520,7 @@ impl<T, V: Version> DoubleEndedIterator for Iter<'_, T, V> {
 
 /// Returned by [`Arena::iter_mut`]
 pub struct IterMut<'a, T, V: Version> {
-    slots: Occupied<core::slice::IterMut<'a, Slot<T, V>>>,
+    pub(super) slots: Occupied<core::slice::IterMut<'a, Slot<T, V>>>,
 }
 
 impl<'a, T, V: Version> Iterator for IterMut<'a, T, V> {
@@ -710,6 +1565,7 @@ pub struct Drain<'a, T, V: Version> {
     slots: Occupied<core::iter::Enumerate<core::slice::IterMut<'a, Slot<T, V>>>>,
     next: &'a mut usize,
     num_elements: &'a mut usize,
+    exhausted: &'a mut usize,
 }
 
 impl<T, V: Version> Drop for Drain<'_, T, V> {
@@ -722,9 +1578,10 @@ impl<'a, T, V: Version> Iterator for Drain<'a, T, V> {
     fn next(&mut self) -> Option<Self::Item> {
         let next = &mut *self.next;
         let num_elements = &mut *self.num_elements;
+        let exhausted = &mut *self.exhausted;
         self.slots.next().map(|(index, slot)| unsafe {
             *num_elements -= 1;
-            slot.remove_unchecked(index, next)
+            slot.remove_unchecked(index, next, exhausted)
         })
     }
 }
@@ -733,9 +1590,10 @@ impl<T, V: Version> DoubleEndedIterator for Drain<'_, T, V> {
     fn next_back(&mut self) -> Option<Self::Item> {
         let next = &mut *self.next;
         let num_elements = &mut *self.num_elements;
+        let exhausted = &mut *self.exhausted;
         self.slots.next_back().map(|(index, slot)| unsafe {
             *num_elements -= 1;
-            slot.remove_unchecked(index, next)
+            slot.remove_unchecked(index, next, exhausted)
         })
     }
 }
@@ -745,6 +1603,7 @@ pub struct DrainFilter<'a, T, V: Version, F: FnMut(&mut T) -> bool> {
     slots: Occupied<core::iter::Enumerate<core::slice::IterMut<'a, Slot<T, V>>>>,
     next: &'a mut usize,
     num_elements: &'a mut usize,
+    exhausted: &'a mut usize,
     filter: F,
     panicked: bool,
 }
@@ -777,7 +1636,7 @@ impl<'a, T, V: Version, F: FnMut(&mut T) -> bool> Iterator for DrainFilter<'a, T
             })
             .err()?;
         *self.num_elements -= 1;
-        Some(unsafe { slot.remove_unchecked(index, self.next) })
+        Some(unsafe { slot.remove_unchecked(index, self.next, self.exhausted) })
     }
 }
 
@@ -795,15 +1654,25 @@ impl<T, V: Version, F: FnMut(&mut T) -> bool> DoubleEndedIterator for DrainFilte
             })
             .err()?;
         *self.num_elements -= 1;
-        Some(unsafe { slot.remove_unchecked(index, self.next) })
+        Some(unsafe { slot.remove_unchecked(index, self.next, self.exhausted) })
     }
 }
 
 /// Returned by [`Arena::entries`]
 pub struct Entries<'a, T, I, V: Version, K> {
-    slots: Occupied<core::iter::Enumerate<core::slice::Iter<'a, Slot<T, V>>>>,
-    ident: &'a I,
-    key: PhantomData<fn() -> K>,
+    pub(super) slots: Occupied<core::iter::Enumerate<core::slice::Iter<'a, Slot<T, V>>>>,
+    pub(super) ident: &'a I,
+    pub(super) key: PhantomData<fn() -> K>,
+}
+
+impl<T, I, V: Version, K> Clone for Entries<'_, T, I, V, K> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self.slots.clone(),
+            ident: self.ident,
+            key: PhantomData,
+        }
+    }
 }
 
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for Entries<'a, T, I, V, K> {
@@ -828,9 +1697,9 @@ impl<T, I, V: Version, K: BuildArenaKey<I, V>> DoubleEndedIterator for Entries<'
 
 /// Returned by [`Arena::entries_mut`]
 pub struct EntriesMut<'a, T, I, V: Version, K> {
-    slots: Occupied<core::iter::Enumerate<core::slice::IterMut<'a, Slot<T, V>>>>,
-    ident: &'a I,
-    key: PhantomData<fn() -> K>,
+    pub(super) slots: Occupied<core::iter::Enumerate<core::slice::IterMut<'a, Slot<T, V>>>>,
+    pub(super) ident: &'a I,
+    pub(super) key: PhantomData<fn() -> K>,
 }
 
 impl<'a, T, I, V: Version, K: BuildArenaKey<I, V>> Iterator for EntriesMut<'a, T, I, V, K> {
@@ -979,6 +1848,54 @@ mod test {
         assert_eq!(items, [0, 3, 6, 9]);
     }
 
+    #[test]
+    fn drain_reset() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        arena.remove(a);
+        let b: crate::Key<usize> = arena.insert(1);
+        assert_ne!(a, b);
+        let _: crate::Key<usize> = arena.insert(2);
+
+        arena.drain_reset();
+        assert!(arena.is_empty());
+        assert_eq!(arena.get(b), None);
+
+        let mut keys = (0..2).map(|i| arena.insert(i)).collect::<Vec<crate::Key<usize>>>();
+        keys.sort_unstable();
+        assert_eq!(keys[0], a);
+    }
+
+    #[test]
+    fn clone_exact() {
+        let mut arena = Arena::new();
+        arena.reserve_exact(16);
+        let keys = (0..10).map(|i| arena.insert(i)).collect::<Vec<crate::Key<usize>>>();
+        arena.remove(keys[3]);
+        arena.remove(keys[7]);
+
+        let mut clone = arena.clone_exact();
+        assert_eq!(clone.capacity(), arena.capacity());
+        for i in 0..4 {
+            assert_eq!(arena.insert::<crate::Key<usize>>(i), clone.insert(i));
+        }
+    }
+
+    #[test]
+    fn indexed() {
+        let mut arena = Arena::new();
+        let mut ins_keys = (0..10).map(|i| arena.insert(i * 10)).collect::<Vec<crate::Key<usize>>>();
+        for i in (0..ins_keys.len()).rev().step_by(3) {
+            let key = ins_keys.remove(i);
+            arena.remove(key);
+        }
+        for (index, value) in arena.indexed_mut() {
+            *value += index;
+        }
+        let indexed = arena.indexed().map(|(index, &value)| (index, value)).collect::<Vec<_>>();
+        assert_eq!(indexed, [(1, 11), (2, 22), (4, 44), (5, 55), (7, 77), (8, 88)]);
+    }
+
     #[test]
     fn iter_keys_insert_only() {
         let mut arena = Arena::new();
@@ -1252,4 +2169,352 @@ mod test {
         assert_eq!(into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
         assert_eq!(rev_into_iter_values, [10, 20, 40, 50, 70, 80, 600, 700, 800, 900]);
     }
+
+    #[test]
+    fn insert_mut() {
+        let mut arena = Arena::new();
+        let (a, value): (usize, &mut i32) = arena.insert_mut(0);
+        *value += 10;
+        let (b, value): (usize, &mut i32) = arena.insert_mut(1);
+        *value += 20;
+        assert_eq!(arena[a], 10);
+        assert_eq!(arena[b], 21);
+    }
+
+    #[test]
+    fn retain_until() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..10).map(|i| arena.insert(i)).collect();
+
+        let mut freed = 0;
+        arena.retain_until(|&mut value| {
+            if freed == 3 {
+                return ControlFlow::Break(())
+            }
+
+            let keep = value % 2 == 0;
+            freed += usize::from(!keep);
+            ControlFlow::Continue(keep)
+        });
+
+        assert_eq!(arena.len(), 7);
+        assert_eq!(arena.iter().copied().collect::<Vec<_>>(), [0, 2, 4, 6, 7, 8, 9]);
+        assert_eq!(arena.get(keys[5]), None);
+        assert_eq!(arena.get(keys[7]), Some(&7));
+    }
+
+    #[test]
+    fn option_key() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b = arena.keys().find(|&key: &crate::Key<usize>| key != a);
+
+        assert_eq!(arena.get(Some(a)), Some(&0));
+        assert_eq!(arena.get(b), None);
+        assert!(!arena.contains(None::<usize>));
+        assert!(!arena.delete(b));
+        assert_eq!(arena.try_remove(Some(a)), Some(0));
+        assert_eq!(arena.get(Some(a)), None);
+    }
+
+    #[test]
+    fn restore_structure() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b: crate::Key<usize> = arena.insert(1);
+        arena.remove(b);
+        let structure = arena.structure_snapshot();
+
+        let c: crate::Key<usize> = arena.insert(2);
+        let d: crate::Key<usize> = arena.insert(3);
+        assert!(!arena.restore_structure(&structure));
+        arena.remove(c);
+        arena.remove(d);
+
+        assert!(arena.restore_structure(&structure));
+        assert_eq!(arena.structure_snapshot().slots[..2], structure.slots[..]);
+        assert_eq!(arena[a], 0);
+
+        let keys: Vec<crate::Key<usize>> = (4..7).map(|i| arena.insert(i)).collect();
+        assert_eq!(keys[0], c);
+        assert_ne!(keys[1], d);
+        assert_eq!(arena.len(), 4);
+        assert_eq!(arena.slots.len(), 4);
+    }
+
+    #[test]
+    fn vacant_entry_at() {
+        let mut arena = Arena::new();
+        let a: usize = arena.vacant_entry_at(3).unwrap().insert(3);
+        assert_eq!(a, 3);
+        assert!(arena.vacant_entry_at(3).is_none());
+
+        let b: usize = arena.vacant_entry_at(1).unwrap().insert(1);
+        assert_eq!(b, 1);
+
+        let rest: Vec<usize> = (0..3).map(|i| arena.insert(i)).collect();
+        assert_eq!(rest, [0, 2, 4]);
+        assert_eq!(arena.len(), 5);
+    }
+
+    #[test]
+    fn get_unchecked_mut2() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(0);
+        let _: usize = arena.insert(1);
+        let c: usize = arena.insert(2);
+        arena.remove(0);
+        let (x, y) = unsafe { arena.get_unchecked_mut2(c, 1) };
+        core::mem::swap(x, y);
+        assert_eq!(arena[1], 2);
+        assert_eq!(arena[c], 1);
+        assert!(!arena.contains(a));
+    }
+
+    #[test]
+    fn drain_map() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+        arena.remove(keys[3]);
+        let stale: crate::Key<usize> = arena.insert(10);
+        arena.remove(stale);
+
+        let arena = arena.drain_map(|key: crate::Key<usize>, value| (*key.id(), value * 10));
+        assert_eq!(arena.len(), 3);
+        assert_eq!(arena[keys[0]], (0, 0));
+        assert_eq!(arena[keys[2]], (2, 20));
+        assert_eq!(arena[keys[4]], (4, 40));
+        assert!(!arena.contains(keys[1]));
+        assert!(!arena.contains(stale));
+    }
+
+    #[test]
+    fn classify() {
+        use crate::KeyStatus;
+
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        arena.remove(a);
+        let b: crate::Key<usize> = arena.insert(1);
+        let c: crate::Key<usize> = arena.insert(2);
+        arena.remove(c);
+
+        assert_eq!(arena.classify(b), KeyStatus::Live);
+        assert_eq!(arena.classify(a), KeyStatus::StaleVersion);
+        assert_eq!(arena.classify(c), KeyStatus::Vacant);
+        assert_eq!(arena.classify(10), KeyStatus::OutOfBounds);
+        assert_eq!(arena.classify(None::<usize>), KeyStatus::OutOfBounds);
+    }
+
+    #[test]
+    #[cfg(feature = "pui")]
+    fn classify_wrong_ident() {
+        use pui_core::dynamic::Dynamic;
+
+        let mut arena = Arena::<_, _>::with_ident(Dynamic::create());
+        let mut other = Arena::<_, _>::with_ident(Dynamic::create());
+        let a: pui_vec::Id<_> = arena.insert(0);
+        let b: pui_vec::Id<_> = other.insert(0);
+        assert_eq!(arena.classify(a), crate::KeyStatus::Live);
+        assert_eq!(arena.classify(b), crate::KeyStatus::WrongIdent);
+    }
+
+    #[test]
+    fn get_batch() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b: crate::Key<usize> = arena.insert(1);
+        arena.remove(a);
+        let c: crate::Key<usize> = arena.insert(2);
+        assert!(arena.get_batch([c, a, b, c]).eq([Some(&2), None, Some(&1), Some(&2)]));
+    }
+
+    #[test]
+    fn get_or_default() {
+        use std::string::String;
+
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(String::from("a"));
+        let b: crate::Key<usize> = arena.insert(String::from("b"));
+        arena.remove(a);
+        assert_eq!(arena.get_or_default(a), "");
+        assert_eq!(arena.get_or_default(b), "b");
+    }
+
+    #[test]
+    fn contains_all() {
+        let mut arena = Arena::new();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b: crate::Key<usize> = arena.insert(1);
+        arena.remove(a);
+        let c: crate::Key<usize> = arena.insert(2);
+        let d = crate::Key::new(10, *c.version());
+        assert_eq!(arena.contains_all(&[c, a, b, d]), [true, false, true, false]);
+    }
+
+    #[test]
+    fn map_values() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[2]);
+
+        let mut doubled = arena.map_values(|&value| value * 2);
+        assert_eq!(doubled.len(), 3);
+        assert_eq!(doubled[keys[0]], 0);
+        assert_eq!(doubled[keys[1]], 2);
+        assert_eq!(doubled[keys[3]], 6);
+        assert!(!doubled.contains(keys[2]));
+
+        let a: crate::Key<usize> = arena.insert(10);
+        let b: crate::Key<usize> = doubled.insert(20);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn iter_clone() {
+        let mut arena = Arena::new();
+        let keys: Vec<usize> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+
+        let mut iter = arena.iter();
+        assert_eq!(iter.next(), Some(&0));
+        let rest = iter.clone().copied().collect::<Vec<_>>();
+        assert_eq!(rest, [2, 3]);
+        assert_eq!(iter.copied().collect::<Vec<_>>(), rest);
+
+        let entries = arena.entries::<usize>();
+        assert!(entries.clone().eq(entries));
+        let keys = arena.keys::<usize>();
+        assert!(keys.clone().eq(keys));
+    }
+
+    #[test]
+    fn eq_by_contents() {
+        let mut a = Arena::new();
+        let keys: Vec<usize> = (0..3).map(|i| a.insert(i)).collect();
+        a.remove(keys[0]);
+        assert_eq!(a.insert::<usize>(0), keys[0]);
+
+        let mut b = Arena::new();
+        let _: Vec<usize> = (0..3).map(|i| b.insert(i)).collect();
+        assert_eq!(a, b);
+
+        b.remove(2usize);
+        assert_ne!(a, b);
+        let _: usize = b.insert(3);
+        assert_ne!(a, b);
+
+        let mut c = Arena::new();
+        let keys: Vec<usize> = (0..4).map(|i| c.insert(i)).collect();
+        c.remove(keys[3]);
+        assert_eq!(a, c);
+    }
+
+    #[test]
+    fn free_slots() {
+        let mut arena = Arena::new();
+        assert_eq!(arena.free_slots().count(), 0);
+        let keys: Vec<usize> = (0..6).map(|i| arena.insert(i)).collect();
+        for &i in &[1, 4, 2] {
+            arena.remove(keys[i]);
+        }
+
+        let free: Vec<_> = arena.free_slots().collect();
+        assert_eq!(free, [2, 4, 1]);
+        for &index in &free {
+            assert_eq!(arena.insert::<usize>(0), index);
+        }
+        assert_eq!(arena.free_slots().count(), 0);
+    }
+
+    #[test]
+    fn trusted_keys() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+
+        arena.with_trusted_keys_mut(|mut arena| {
+            let trusted: Vec<_> = keys.iter().filter_map(|key| arena.validate_once(key)).collect();
+            assert_eq!(trusted.iter().map(|key| key.index()).collect::<Vec<_>>(), [0, 2, 3]);
+
+            for &key in &trusted {
+                *arena.get_mut(key) *= 10;
+            }
+            assert_eq!(trusted.iter().map(|&key| *arena.get(key)).collect::<Vec<_>>(), [0, 20, 30]);
+        });
+
+        assert_eq!(unsafe { *arena.get_unchecked_key(&keys[2]) }, 20);
+    }
+
+    #[test]
+    fn trusted_iter() {
+        let mut arena = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[2]);
+
+        arena.with_trusted_keys_mut(|mut arena| {
+            let trusted: Vec<_> = arena.keys().collect();
+            assert_eq!(trusted.iter().map(|key| key.index()).collect::<Vec<_>>(), [0, 1, 3]);
+
+            for key in trusted {
+                *arena.get_mut(key) += 1;
+            }
+        });
+
+        let values = arena.with_trusted_keys(|arena| arena.iter().map(|(key, _)| *arena.get(key)).collect::<Vec<_>>());
+        assert_eq!(values, [1, 2, 4]);
+    }
+
+    #[test]
+    fn default_ident() {
+        #[derive(Default)]
+        struct Ident;
+
+        let mut arena = Arena::<_, Ident>::default();
+        let a: crate::Key<usize> = arena.insert(0);
+        let b: crate::Key<usize> = arena.insert(1);
+        assert_eq!(arena.remove(a), 0);
+        assert_eq!(arena[b], 1);
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn reserve_zero() {
+        let mut arena = Arena::new();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), 0);
+
+        let keys: Vec<usize> = (0..5).map(|i| arena.insert(i)).collect();
+        arena.remove(keys[1]);
+        let capacity = arena.capacity();
+        arena.reserve(0);
+        arena.reserve_exact(0);
+        assert_eq!(arena.capacity(), capacity);
+    }
+
+    #[test]
+    fn clone_from() {
+        let mut source = Arena::new();
+        let keys: Vec<crate::Key<usize>> = (0..6).map(|i| source.insert(i)).collect();
+        source.remove(keys[1]);
+        source.remove(keys[4]);
+
+        let mut arena = Arena::new();
+        for i in 0..10 {
+            let _: usize = arena.insert(i * 10);
+        }
+        arena.remove(3);
+
+        let capacity = arena.capacity();
+        arena.clone_from(&source);
+        assert_eq!(arena.capacity(), capacity);
+        assert_eq!(arena.len(), source.len());
+        assert!(keys.iter().all(|&key| arena.get(key) == source.get(key)));
+
+        let a: crate::Key<usize> = arena.insert(10);
+        let b: crate::Key<usize> = source.insert(10);
+        assert_eq!(a, b);
+    }
 }
@@ -0,0 +1,176 @@
+use std::vec::Vec;
+
+use super::{Arena, Iter, IterMut};
+use crate::{
+    version::{DefaultVersion, Version},
+    ArenaKey, BuildArenaKey,
+};
+
+/// A sparse arena that stores some metadata `M` alongside each value
+///
+/// The metadata is stored separately from the values, so iterating over
+/// the values doesn't touch the metadata, and the other way around.
+///
+/// ```
+/// use pui_arena::{base::sparse::MetaArena, Key};
+///
+/// let mut arena = MetaArena::new();
+/// let a: Key<usize> = arena.insert("a", 0_u8);
+/// let b: Key<usize> = arena.insert("b", 1);
+///
+/// *arena.get_meta_mut(a).unwrap() |= 2;
+/// assert_eq!(arena.get_meta(a), Some(&2));
+/// assert_eq!(arena.remove(b), ("b", 1));
+/// assert!(arena.iter().eq(&["a"]));
+/// ```
+#[derive(Debug)]
+pub struct MetaArena<T, M, I = (), V: Version = DefaultVersion> {
+    arena: Arena<T, I, V>,
+    meta: Vec<Option<M>>,
+}
+
+impl<T, M> MetaArena<T, M> {
+    /// Create a new arena
+    pub const fn new() -> Self { Self::with_arena(Arena::INIT) }
+}
+
+impl<T, M> Default for MetaArena<T, M> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T, M, I, V: Version> MetaArena<T, M, I, V> {
+    const fn with_arena(arena: Arena<T, I, V>) -> Self { Self { arena, meta: Vec::new() } }
+
+    /// Create a new arena with the given identifier
+    pub fn with_ident(ident: I) -> Self { Self::with_arena(Arena::with_ident(ident)) }
+
+    /// The arena that holds the values
+    pub fn arena(&self) -> &Arena<T, I, V> { &self.arena }
+
+    /// Returns true if the arena is empty
+    pub fn is_empty(&self) -> bool { self.arena.is_empty() }
+
+    /// Returns the number of elements in this arena
+    pub fn len(&self) -> usize { self.arena.len() }
+
+    /// Clear the arena without reducing it's capacity
+    pub fn clear(&mut self) {
+        self.arena.delete_all();
+        self.meta.clear();
+    }
+
+    /// Insert a value and it's metadata in the arena, returning key assigned to the value
+    pub fn insert<K: BuildArenaKey<I, V>>(&mut self, value: T, meta: M) -> K {
+        let entry = self.arena.vacant_entry();
+        let index = entry.key::<usize>();
+
+        if self.meta.len() <= index {
+            self.meta.resize_with(index + 1, || None);
+        }
+
+        self.meta[index] = Some(meta);
+        entry.insert(value)
+    }
+
+    /// Return true if a value is associated with the given key
+    pub fn contains<K: ArenaKey<I, V>>(&self, key: K) -> bool { self.arena.contains(key) }
+
+    /// Remove and return the value associated with the given key, and it's metadata
+    ///
+    /// # Panics
+    ///
+    /// If the key is not associated with a value
+    #[track_caller]
+    pub fn remove<K: ArenaKey<I, V>>(&mut self, key: K) -> (T, M) {
+        self.try_remove(key)
+            .expect("Could not remove from an `Arena` using a stale `Key`")
+    }
+
+    /// Remove and return the value associated with the given key, and it's metadata
+    ///
+    /// Returns `None` if key is not associated with a value.
+    pub fn try_remove<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<(T, M)> {
+        let index = self.arena.find(&key)?;
+        let meta = self.meta[index].take()?;
+        Some((unsafe { self.arena.remove_unchecked(index) }, meta))
+    }
+
+    /// Return a shared reference to the value associated with the given key
+    pub fn get<K: ArenaKey<I, V>>(&self, key: K) -> Option<&T> { self.arena.get(key) }
+
+    /// Return a unique reference to the value associated with the given key
+    pub fn get_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<&mut T> { self.arena.get_mut(key) }
+
+    /// Return a shared reference to the metadata of the value associated with the given key
+    pub fn get_meta<K: ArenaKey<I, V>>(&self, key: K) -> Option<&M> {
+        let index = self.arena.find(&key)?;
+        self.meta[index].as_ref()
+    }
+
+    /// Return a unique reference to the metadata of the value associated with the given key
+    pub fn get_meta_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<&mut M> {
+        let index = self.arena.find(&key)?;
+        self.meta[index].as_mut()
+    }
+
+    /// Return unique references to the value associated with the given key, and it's metadata
+    pub fn get_with_meta_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<(&mut T, &mut M)> {
+        let index = self.arena.find(&key)?;
+        let meta = self.meta[index].as_mut()?;
+        Some((unsafe { self.arena.get_unchecked_mut(index) }, meta))
+    }
+
+    /// An iterator over the keys of the arena, in no particular order
+    pub fn keys<K: BuildArenaKey<I, V>>(&self) -> super::Keys<'_, T, I, V, K> { self.arena.keys() }
+
+    /// An iterator of shared references to values of the arena,
+    /// in no particular order
+    pub fn iter(&self) -> Iter<'_, T, V> { self.arena.iter() }
+
+    /// An iterator of unique references to values of the arena,
+    /// in no particular order
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, V> { self.arena.iter_mut() }
+
+    /// An iterator of shared references to the metadata of the values in the arena,
+    /// in no particular order
+    pub fn metas(&self) -> impl Iterator<Item = &M> { self.meta.iter().filter_map(Option::as_ref) }
+
+    /// An iterator of unique references to the metadata of the values in the arena,
+    /// in no particular order
+    pub fn metas_mut(&mut self) -> impl Iterator<Item = &mut M> { self.meta.iter_mut().filter_map(Option::as_mut) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MetaArena;
+    use crate::Key;
+
+    #[test]
+    fn meta() {
+        let mut arena = MetaArena::new();
+        let a: Key<usize> = arena.insert(0, 'a');
+        let b: Key<usize> = arena.insert(1, 'b');
+
+        assert_eq!(arena.remove(a), (0, 'a'));
+        assert_eq!(arena.get_meta(a), None);
+        assert_eq!(arena.try_remove(a), None);
+
+        let c: Key<usize> = arena.insert(2, 'c');
+        assert_eq!(c.id(), a.id());
+        assert_eq!(arena.get_meta(c), Some(&'c'));
+
+        let (value, meta) = arena.get_with_meta_mut(b).unwrap();
+        *value += 10;
+        *meta = 'B';
+        assert_eq!(arena.get(b), Some(&11));
+
+        let mut metas = arena.metas().copied().collect::<std::vec::Vec<_>>();
+        metas.sort_unstable();
+        assert_eq!(metas, ['B', 'c']);
+        assert_eq!(arena.len(), 2);
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(arena.metas().count(), 0);
+    }
+}
@@ -0,0 +1,85 @@
+//! Read-only arenas that can be shared between threads
+//!
+//! After an arena is built, it can be frozen with `Arena::freeze` on any of the
+//! arenas in [`base`](crate::base). A [`Frozen`] arena dereferences to the backing
+//! arena, so only the methods that take `&self` are available, and it can be cheaply
+//! cloned to share it between threads. Once all but one clone are dropped, the arena
+//! can be mutated again with [`Frozen::thaw`].
+//!
+//! ```rust
+//! use pui_arena::base::sparse::Arena;
+//!
+//! let mut arena = Arena::new();
+//! let a: usize = arena.insert(10);
+//! let frozen = arena.freeze();
+//!
+//! let reader = frozen.clone();
+//! let value = std::thread::spawn(move || reader[a]).join().unwrap();
+//! assert_eq!(value, 10);
+//!
+//! let mut arena = frozen.thaw().unwrap();
+//! arena.remove(a);
+//! ```
+
+use core::ops::Deref;
+use std::sync::Arc;
+
+use crate::version::Version;
+
+/// A read-only arena that can be cheaply cloned, created by `Arena::freeze`
+#[derive(Debug)]
+pub struct Frozen<A>(Arc<A>);
+
+impl<A> Clone for Frozen<A> {
+    fn clone(&self) -> Self { Self(self.0.clone()) }
+}
+
+impl<A> Frozen<A> {
+    /// Create a new frozen arena
+    pub fn new(arena: A) -> Self { Self(Arc::new(arena)) }
+
+    /// Get back the backing arena, or `Err(self)` if there are other clones of this `Frozen`
+    pub fn thaw(self) -> Result<A, Self> { Arc::try_unwrap(self.0).map_err(Self) }
+
+    /// Returns true if this isn't shared with any other clones
+    pub fn is_unique(&self) -> bool { Arc::strong_count(&self.0) == 1 }
+}
+
+impl<A> Deref for Frozen<A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target { &self.0 }
+}
+
+macro_rules! imp_frozen {
+    ($($arena:ident)*) => {$(
+        impl<T, I, V: Version> crate::base::$arena::Arena<T, I, V> {
+            /// Make this arena read-only, so that it can be cheaply cloned and shared between threads
+            pub fn freeze(self) -> Frozen<Self> { Frozen::new(self) }
+        }
+    )*};
+}
+
+imp_frozen! { sparse hop dense }
+
+#[cfg(test)]
+mod test {
+    use crate::base::hop::Arena;
+
+    #[test]
+    fn thaw() {
+        let mut arena = Arena::new();
+        let a: usize = arena.insert(0);
+        let frozen = arena.freeze();
+        let other = frozen.clone();
+        assert!(!frozen.is_unique());
+        assert_eq!(other[a], 0);
+
+        let frozen = frozen.thaw().unwrap_err();
+        drop(other);
+        assert!(frozen.is_unique());
+
+        let mut arena = frozen.thaw().ok().unwrap();
+        assert_eq!(arena.remove(a), 0);
+    }
+}
@@ -0,0 +1,225 @@
+//! A table that assigns compact `u32` handles to versioned keys
+//!
+//! Versioned keys are larger than a `u32`, which makes them awkward to hand to
+//! APIs that expect small integer handles (for example across an FFI boundary).
+//! A [`HandleTable`] assigns each key a handle, and translates handles back to keys.
+//! A key keeps it's handle until it's removed from the table, after which the handle
+//! may be reused for another key.
+//!
+//! ```rust
+//! use pui_arena::{base::sparse::Arena, handle_table::HandleTable, Key};
+//!
+//! let mut arena = Arena::new();
+//! let mut handles = HandleTable::<Key<usize, _>>::new();
+//!
+//! let a: Key<usize, _> = arena.insert("a");
+//! let b: Key<usize, _> = arena.insert("b");
+//! let ha = handles.insert(a);
+//! let hb = handles.insert(b);
+//!
+//! assert_eq!(handles.insert(a), ha);
+//! assert_eq!(handles.key(hb), Some(&b));
+//!
+//! arena.remove(a);
+//! assert_eq!(handles.remove(a), Some(ha));
+//!
+//! // the removed key's handle is reused
+//! let c: Key<usize, _> = arena.insert("c");
+//! assert_eq!(handles.insert(c), ha);
+//! assert_eq!(handles.handle(&a), None);
+//! ```
+
+use core::marker::PhantomData;
+use std::vec::Vec;
+
+use crate::{
+    base::sparse::Arena,
+    version::{DefaultVersion, Unversioned, Version},
+    ArenaKey,
+};
+
+/// A two-way mapping between keys and compact `u32` handles
+///
+/// See the [module docs](self) for details
+pub struct HandleTable<K, I = (), V = DefaultVersion> {
+    keys: Arena<K, (), Unversioned>,
+    handles: Vec<Option<u32>>,
+    mark: PhantomData<fn() -> (I, V)>,
+}
+
+impl<K: Clone, I, V> Clone for HandleTable<K, I, V> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            handles: self.handles.clone(),
+            mark: PhantomData,
+        }
+    }
+}
+
+impl<K: core::fmt::Debug, I, V> core::fmt::Debug for HandleTable<K, I, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.keys.entries::<usize>()).finish()
+    }
+}
+
+impl<K, I, V> Default for HandleTable<K, I, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K, I, V> HandleTable<K, I, V> {
+    /// Create a new, empty handle table
+    pub fn new() -> Self {
+        Self {
+            keys: Arena::INIT,
+            handles: Vec::new(),
+            mark: PhantomData,
+        }
+    }
+
+    /// Returns true if no keys have a handle
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+
+    /// Returns the number of keys that have a handle
+    pub fn len(&self) -> usize { self.keys.len() }
+
+    /// Get the key associated with `handle`
+    pub fn key(&self, handle: u32) -> Option<&K> { self.keys.get(handle as usize) }
+
+    /// Remove all keys from the table, this invalidates all handles
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.handles.clear();
+    }
+
+    /// An iterator over all handles and their keys
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &K)> {
+        self.keys.entries::<usize>().map(|(handle, key)| (handle as u32, key))
+    }
+}
+
+impl<K: ArenaKey<I, V>, I, V: Version> HandleTable<K, I, V>
+where
+    V::Save: PartialEq,
+{
+    fn find(&self, key: &K) -> Option<u32> {
+        let handle = (*self.handles.get(key.index())?)?;
+        let stored = self.keys.get(handle as usize)?;
+        if stored.version() == key.version() {
+            Some(handle)
+        } else {
+            None
+        }
+    }
+
+    /// Get the handle associated with `key`
+    pub fn handle(&self, key: &K) -> Option<u32> { self.find(key) }
+
+    /// Returns true if `key` has a handle
+    pub fn contains(&self, key: &K) -> bool { self.find(key).is_some() }
+
+    /// Get the handle associated with `key`, assigning it a new handle if it doesn't have one
+    ///
+    /// If a key with the same index but a different version has a handle,
+    /// it's replaced by `key` and it's handle is freed.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than `u32::MAX` handles, or if `key`'s index is `usize::MAX`,
+    /// which no arena can hold (for example `None` keys)
+    pub fn insert(&mut self, key: K) -> u32 {
+        if let Some(handle) = self.find(&key) {
+            return handle
+        }
+
+        let index = key.index();
+        assert!(index != usize::MAX, "tried to create a handle for a key that isn't in any arena");
+
+        if let Some(stale) = self.handles.get_mut(index).and_then(Option::take) {
+            self.keys.remove(stale as usize);
+        }
+
+        let entry = self.keys.vacant_entry();
+        let handle = entry.key::<usize>();
+        assert!(handle < u32::MAX as usize, "tried to create more than `u32::MAX` handles");
+        let handle = handle as u32;
+        entry.insert::<usize>(key);
+
+        if self.handles.len() <= index {
+            self.handles.resize(index + 1, None);
+        }
+
+        self.handles[index] = Some(handle);
+
+        handle
+    }
+
+    /// Remove `key` from the table, returning it's handle
+    ///
+    /// The handle may be reused by later calls to `insert`
+    pub fn remove(&mut self, key: K) -> Option<u32> {
+        let handle = self.find(&key)?;
+        self.handles[key.index()] = None;
+        self.keys.remove(handle as usize);
+        Some(handle)
+    }
+
+    /// Remove `handle` from the table, returning it's key
+    ///
+    /// The handle may be reused by later calls to `insert`
+    pub fn remove_handle(&mut self, handle: u32) -> Option<K> {
+        let key = self.keys.try_remove(handle as usize)?;
+        self.handles[key.index()] = None;
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HandleTable;
+    use crate::{base::sparse::Arena, Key};
+
+    #[test]
+    fn stale_keys() {
+        let mut arena = Arena::<u8>::new();
+        let mut handles = HandleTable::<Key<usize, _>>::new();
+
+        let a: Key<usize, _> = arena.insert(0);
+        let ha = handles.insert(a);
+        arena.remove(a);
+
+        // `b` reuses `a`'s slot, so it replaces `a` in the table
+        let b: Key<usize, _> = arena.insert(1);
+        assert_eq!(b.id(), a.id());
+        assert!(!handles.contains(&b));
+        let hb = handles.insert(b);
+        assert_eq!(hb, ha);
+        assert_eq!(handles.len(), 1);
+        assert_eq!(handles.handle(&a), None);
+        assert_eq!(handles.remove(a), None);
+
+        assert_eq!(handles.remove_handle(hb), Some(b));
+        assert!(handles.is_empty());
+        assert_eq!(handles.key(hb), None);
+    }
+
+    #[test]
+    fn none_key() {
+        let mut arena = Arena::<u8>::new();
+        let mut handles = HandleTable::<Option<Key<usize, _>>>::new();
+
+        let a: Key<usize, _> = arena.insert(0);
+        let ha = handles.insert(Some(a));
+        assert!(!handles.contains(&None));
+        assert_eq!(handles.handle(&None), None);
+        assert_eq!(handles.remove(None), None);
+        assert_eq!(handles.remove(Some(a)), Some(ha));
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't in any arena")]
+    fn insert_none_key() {
+        let mut handles = HandleTable::<Option<Key<usize>>>::new();
+        handles.insert(None);
+    }
+}
@@ -0,0 +1,104 @@
+//! An interner built on top of [`base::sparse::Arena`](crate::base::sparse::Arena)
+//!
+//! Interning a value returns the key of an existing equal value if there is
+//! one, otherwise the value is inserted into the arena. Values are never removed
+//! from an interner, so the keys it produces are always valid.
+//!
+//! ```rust
+//! use pui_arena::interner::Interner;
+//!
+//! let mut interner = Interner::<_>::new();
+//! let a = interner.intern("hello");
+//! let b = interner.intern("world");
+//! assert_eq!(interner.intern("hello"), a);
+//! assert_ne!(a, b);
+//! assert_eq!(*interner.resolve(b), "world");
+//! ```
+
+extern crate std;
+
+use core::hash::Hash;
+use std::collections::HashMap;
+
+use crate::{
+    base::sparse::Arena,
+    version::{DefaultVersion, Version},
+    ArenaKey, BuildArenaKey,
+};
+
+/// An interner, which deduplicates values and assigns each one a key
+#[derive(Debug, Clone)]
+pub struct Interner<T, K = usize, V: Version = DefaultVersion> {
+    arena: Arena<T, (), V>,
+    map: HashMap<T, K>,
+}
+
+impl<T, K, V: Version> Default for Interner<T, K, V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T, K, V: Version> Interner<T, K, V> {
+    /// Create a new, empty interner
+    pub fn new() -> Self {
+        Self {
+            arena: Arena::INIT,
+            map: HashMap::new(),
+        }
+    }
+
+    /// Returns true if no values have been interned
+    pub fn is_empty(&self) -> bool { self.arena.is_empty() }
+
+    /// Returns the number of unique values that have been interned
+    pub fn len(&self) -> usize { self.arena.len() }
+
+    /// Get the backing arena
+    pub fn arena(&self) -> &Arena<T, (), V> { &self.arena }
+
+    /// Return a shared reference to the value associated with the given key.
+    ///
+    /// Panics if the key was not produced by this interner
+    #[track_caller]
+    pub fn resolve(&self, key: K) -> &T
+    where
+        K: ArenaKey<(), V>,
+    {
+        &self.arena[key]
+    }
+}
+
+impl<T: Clone + Hash + Eq, K: Copy + BuildArenaKey<(), V>, V: Version> Interner<T, K, V> {
+    /// Return the key associated with a value equal to `value`,
+    /// or insert the value and return it's new key
+    pub fn intern(&mut self, value: T) -> K {
+        if let Some(&key) = self.map.get(&value) {
+            return key
+        }
+
+        let key = self.arena.insert(value.clone());
+        self.map.insert(value, key);
+        key
+    }
+
+    /// Return the key associated with a value equal to `value`,
+    /// or `None` if no such value was interned
+    pub fn get(&self, value: &T) -> Option<K> { self.map.get(value).copied() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let mut interner = Interner::<_>::new();
+        let a = interner.intern(0);
+        let b = interner.intern(1);
+        assert_eq!(interner.intern(0), a);
+        assert_eq!(interner.get(&1), Some(b));
+        assert_eq!(interner.get(&2), None);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(*interner.resolve(a), 0);
+        assert_eq!(*interner.resolve(b), 1);
+    }
+}
@@ -41,6 +41,9 @@
 //! * If you want reasonable iteration speed and also fast access/delete, or if [`dense`](base::dense)
 //! is to memory heavy, use [`hop`](base::hop)
 //!
+//! * If you create many small, short-lived arenas, use [`inline`](base::inline),
+//!   which is a sparse arena that stores it's first few slots without allocating
+//!
 //! You can read about the details of how each works in the corrosponding module docs
 //!
 //! # Performance characteristics
@@ -69,7 +72,7 @@
 //! the [`ArenaKey`](ArenaKey) trait, and the [`BuildArenaKey`](BuildArenaKey) trait.
 //!
 //! `Version` specifies the behavior of the arenas.
-//! `pui-arena` provides three implementations,
+//! `pui-arena` provides five implementations,
 //! see [`Version`](version::Version) for more details:
 //!
 //! * [`DefaultVersion`](version::DefaultVersion)
@@ -79,6 +82,8 @@
 //!             slots will be "leaked", and iteraton performance may degrade
 //!             but, this is unlikely, unless the same slot is reused over
 //!             2 billion times
+//! * [`NonMaxVersion`](version::NonMaxVersion) -
+//!     * Like `DefaultVersion`, but `Option<Key<_, _>>` is the same size as `Key<_, _>`
 //! * [`TinyVersion`](version::TinyVersion) -
 //!     * Ensures that all keys produced by `insert` are unique
 //!     * backed by a `u8`, if items are inserted/removed many times,
@@ -86,6 +91,9 @@
 //! * [`Unversioned`](version::Unversioned) -
 //!     * Keys produced by `insert` are not guartneed to be unique
 //!     * slots will never be "leaked"
+//! * [`Tagged`](version::Tagged) -
+//!     * Wraps another version, and adds a small tag chosen by `insert_tagged`
+//!     * Keys with the wrong tag are treated like stale keys
 //!
 //! [`ArenaKey`] specifies the behavior of keys into arenas.
 //! `pui-arena` provides a number of implementations. See [`ArenaKey`]
@@ -104,6 +112,11 @@
 //!         and use `pui_vec::Id` instead. It is safe, and also guartnees bound check elision
 //! * [`ScopedKey<'_, _>`](scoped::ScopedKey) - only allows access into scoped arenas
 //!     (otherwise identical to `Key`)
+//! * [`Option<K>`](Option) - `Some(key)` behaves exactly like `key`, and `None`
+//!     is never associated with a value
+//! * `&K` - behaves exactly like `K`
+//!     * allows passing keys that aren't `Copy` to methods like `remove` without moving them
+//!     * validating a key only borrows it, so a key's token is never cloned
 //!
 //! enabled with the `pui` feature
 //!
@@ -113,6 +126,39 @@
 //! [`BuildArenaKey`] specifies how arenas should create keys, all implementors of [`ArenaKey`]
 //! provided by this crate also implement [`BuildArenaKey`] except for [`TrustedIndex`].
 //!
+//! Your own newtypes around keys can implement both traits with the [`impl_arena_key`] macro.
+//!
+//! # Using [`pui_vec::Id`] with a `PuiVec`
+//!
+//! Each identifier is owned by exactly one collection, and an `Id` is only
+//! trusted to be in bounds by the collection that owns the identifier that created it.
+//! So an arena and a parallel `PuiVec` can't share an identifier, but you can move an
+//! `Id` between them with [`parse_key`](base::sparse::Arena::parse_key) and
+//! [`PuiVec::parse_id`](pui_vec::PuiVec::parse_id), which check the bounds once.
+//! The new `Id` then elides bounds checks on the collection that created it.
+//!
+//! ```rust
+//! use pui_arena::base::sparse::Arena;
+//! use pui_core::dynamic::Dynamic;
+//! use pui_vec::{Id, PuiVec};
+//!
+//! let mut arena = Arena::<_, _>::with_ident(Dynamic::create());
+//! let mut names = PuiVec::new(Dynamic::create());
+//!
+//! let a: Id<_> = arena.insert(10);
+//! let _: Id<_> = names.push("a");
+//!
+//! let name = names.parse_id(a.get()).unwrap();
+//! assert_eq!(names[name], "a");
+//!
+//! let key: Id<_> = arena.parse_key(name.get()).unwrap();
+//! assert_eq!(arena[key], 10);
+//! ```
+//!
+//! Arenas also accept an `Id` that was created by another collection, but it's
+//! treated like a `usize`, so it's bounds checked on every access. A `PuiVec` panics on
+//! foreign `Id`s instead.
+//!
 //! # Custom arenas
 //!
 //! You can newtype arenas with the [`newtype`] macro, or the features: `slab`, `slotmap`, or `scoped`.
@@ -183,15 +229,24 @@ pub extern crate alloc as std;
 pub mod version;
 
 mod arena_access;
-pub use arena_access::{ArenaKey, BuildArenaKey, CompleteValidator, Key, Validator};
+pub use arena_access::{ArenaKey, BuildArenaKey, CompleteValidator, Key, KeyStatus, Validator};
 
 /// the core implementations of different types of arenas
 pub mod base {
     pub mod dense;
     pub mod hop;
+    pub mod inline;
     pub mod sparse;
 }
 
+// `Frozen` is built on `Arc`, which needs pointer-sized atomics
+#[cfg(target_has_atomic = "ptr")]
+#[cfg_attr(docsrs, doc(cfg(target_has_atomic = "ptr")))]
+pub mod frozen;
+pub mod handle_table;
+pub mod observer;
+pub mod with_key;
+
 #[cfg(feature = "scoped")]
 #[cfg_attr(docsrs, doc(cfg(feature = "scoped")))]
 pub mod scoped;
@@ -203,6 +258,9 @@ pub mod slab;
 #[cfg(feature = "slotmap")]
 #[cfg_attr(docsrs, doc(cfg(feature = "slotmap")))]
 pub mod slotmap;
+#[cfg(feature = "interner")]
+#[cfg_attr(docsrs, doc(cfg(feature = "interner")))]
+pub mod interner;
 
 #[doc(hidden)]
 #[cfg(feature = "pui")]
@@ -222,6 +280,34 @@ impl TrustedIndex {
     pub unsafe fn new(index: usize) -> Self { Self(index) }
 }
 
+/// A key that was validated by a [`Trusted`](base::sparse::Trusted) view of an arena
+///
+/// The `'brand` ties it to the view that created it, so it can only be
+/// used with that view, and only while the arena can't remove values.
+#[derive(Clone, Copy)]
+pub struct TrustedKey<'brand> {
+    index: usize,
+    brand: core::marker::PhantomData<fn(&'brand ()) -> &'brand ()>,
+}
+
+impl TrustedKey<'_> {
+    /// Create a new `TrustedKey`
+    ///
+    /// # Safety
+    ///
+    /// The slot at `index` must be occupied for as long as `'brand` is alive
+    #[inline]
+    pub(crate) unsafe fn new(index: usize) -> Self {
+        Self {
+            index,
+            brand: core::marker::PhantomData,
+        }
+    }
+
+    /// The index of this key
+    pub fn index(&self) -> usize { self.index }
+}
+
 struct SetOnDrop<'a>(&'a mut bool);
 
 impl Drop for SetOnDrop<'_> {
@@ -355,8 +441,12 @@ macro_rules! __newtype {
             pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> { VacantEntry(self.0.vacant_entry()) }
             /// see [`Arena::insert`](imp::Arena::insert)
             pub fn insert(&mut self, value: T) -> Key { self.0.insert(value) }
+            /// see [`Arena::insert_mut`](imp::Arena::insert_mut)
+            pub fn insert_mut(&mut self, value: T) -> (Key, &mut T) { self.0.insert_mut(value) }
             /// see [`Arena::contains`](imp::Arena::contains)
             pub fn contains(&self, key: Key) -> bool { self.0.contains(key) }
+            /// see [`Arena::classify`](imp::Arena::classify)
+            pub fn classify(&self, key: Key) -> $crate::KeyStatus { self.0.classify(key) }
             /// see [`Arena::remove`](imp::Arena::remove)
             pub fn remove(&mut self, key: Key) -> T { self.0.remove(key) }
             /// see [`Arena::try_remove`](imp::Arena::try_remove)
@@ -490,9 +580,9 @@ macro_rules! __newtype {
             /// The version for [`Arena`]
             $item_vis type Version = $version;
             /// Returned from [`Arena::iter`]
-            $item_vis type Iter<'a, T> = $crate::core::slice::Iter<'a, T>;
+            $item_vis type Iter<'a, T> = $crate::base::dense::Iter<'a, T>;
             /// Returned from [`Arena::iter_mut`]
-            $item_vis type IterMut<'a, T> = $crate::core::slice::IterMut<'a, T>;
+            $item_vis type IterMut<'a, T> = $crate::base::dense::IterMut<'a, T>;
             /// Returned from [`Arena::into_iter`]
             $item_vis type IntoIter<T> = $crate::std::vec::IntoIter<T>;
 
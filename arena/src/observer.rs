@@ -0,0 +1,165 @@
+//! Arenas that notify an [`ArenaObserver`] whenever a value is inserted or removed
+//!
+//! This is useful for keeping a derived index in sync with an arena without
+//! wrapping every call site. Create an observed arena with `Arena::with_observer`
+//! on any of the arenas in [`base`](crate::base). Arenas without an observer are
+//! unaffected and pay no overhead.
+//!
+//! [`Observed`] dereferences to the backing arena, so all read-only methods are
+//! available directly. Methods that could insert or remove values without notifying
+//! the observer are only reachable through [`Observed::into_inner`].
+
+use core::ops::Deref;
+
+use crate::{version::Version, ArenaKey, BuildArenaKey};
+
+/// An observer that is notified of inserts and removals on an [`Observed`] arena
+pub trait ArenaObserver<T, K> {
+    /// Called after `value` was inserted at `key`
+    fn on_insert(&mut self, key: &K, value: &T);
+
+    /// Called when the `value` at `key` is removed from the arena
+    fn on_remove(&mut self, key: &K, value: &T);
+}
+
+impl<T, K, O: ?Sized + ArenaObserver<T, K>> ArenaObserver<T, K> for &mut O {
+    fn on_insert(&mut self, key: &K, value: &T) { O::on_insert(self, key, value) }
+
+    fn on_remove(&mut self, key: &K, value: &T) { O::on_remove(self, key, value) }
+}
+
+/// An arena that notifies an [`ArenaObserver`] when values are inserted or removed
+pub struct Observed<A, O> {
+    arena: A,
+    observer: O,
+}
+
+impl<A, O> Observed<A, O> {
+    /// Create a new observed arena from an arena and an observer
+    pub fn new(arena: A, observer: O) -> Self { Self { arena, observer } }
+
+    /// Get a shared reference to the observer
+    pub fn observer(&self) -> &O { &self.observer }
+
+    /// Get a unique reference to the observer
+    pub fn observer_mut(&mut self) -> &mut O { &mut self.observer }
+
+    /// Decompose into the backing arena and observer
+    pub fn into_inner(self) -> (A, O) { (self.arena, self.observer) }
+}
+
+impl<A, O> Deref for Observed<A, O> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target { &self.arena }
+}
+
+macro_rules! imp_observed {
+    ($($arena:ident)*) => {$(
+        impl<T, I, V: Version> crate::base::$arena::Arena<T, I, V> {
+            /// Create a new arena with the given identifier, which notifies `observer`
+            /// whenever a value is inserted or removed
+            pub fn with_observer<O>(ident: I, observer: O) -> Observed<Self, O> {
+                Observed::new(Self::with_ident(ident), observer)
+            }
+        }
+
+        impl<T, I, V: Version, O> Observed<crate::base::$arena::Arena<T, I, V>, O> {
+            /// Insert a value in the arena, returning key assigned to the value.
+            ///
+            /// The observer is notified after the value is inserted
+            pub fn insert<K: BuildArenaKey<I, V>>(&mut self, value: T) -> K
+            where
+                O: ArenaObserver<T, K>,
+            {
+                let key: K = self.arena.insert(value);
+                let value = unsafe { self.arena.get_unchecked(key.index()) };
+                self.observer.on_insert(&key, value);
+                key
+            }
+
+            /// Remove and return the value associated with the given key.
+            ///
+            /// The observer is notified before the value is returned
+            ///
+            /// Panics if key is not associated with a value.
+            #[track_caller]
+            pub fn remove<K: ArenaKey<I, V>>(&mut self, key: K) -> T
+            where
+                O: ArenaObserver<T, K>,
+            {
+                self.try_remove(key)
+                    .expect("Could not remove from an `Arena` using a stale `Key`")
+            }
+
+            /// Remove and return the value associated with the given key.
+            ///
+            /// The observer is notified before the value is returned
+            ///
+            /// Returns `None` if key is not associated with a value.
+            pub fn try_remove<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<T>
+            where
+                O: ArenaObserver<T, K>,
+            {
+                let value = self.arena.try_remove(&key)?;
+                self.observer.on_remove(&key, &value);
+                Some(value)
+            }
+
+            /// Removes the value associated with the given key.
+            ///
+            /// The observer is notified before the value is dropped
+            ///
+            /// Returns true if the value was removed, an false otherwise
+            pub fn delete<K: ArenaKey<I, V>>(&mut self, key: K) -> bool
+            where
+                O: ArenaObserver<T, K>,
+            {
+                match self.arena.get(&key) {
+                    Some(value) => self.observer.on_remove(&key, value),
+                    None => return false,
+                }
+
+                self.arena.delete(key)
+            }
+
+            /// Return a unique reference to the value associated with the given key.
+            ///
+            /// If the given key is not associated with a value, then None is returned.
+            pub fn get_mut<K: ArenaKey<I, V>>(&mut self, key: K) -> Option<&mut T> { self.arena.get_mut(key) }
+        }
+    )*};
+}
+
+imp_observed! { sparse hop dense }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{base::sparse::Arena, version::DefaultVersion};
+    use std::vec::Vec;
+
+    #[derive(Default)]
+    struct Log(Vec<(bool, usize, i32)>);
+
+    impl ArenaObserver<i32, usize> for Log {
+        fn on_insert(&mut self, &key: &usize, &value: &i32) { self.0.push((true, key, value)) }
+
+        fn on_remove(&mut self, &key: &usize, &value: &i32) { self.0.push((false, key, value)) }
+    }
+
+    #[test]
+    fn basic() {
+        let mut arena = Arena::<_, _, DefaultVersion>::with_observer((), Log::default());
+
+        let a: usize = arena.insert(10);
+        let b: usize = arena.insert(20);
+        assert_eq!(arena.remove(a), 10);
+        assert!(arena.delete(b));
+        assert!(!arena.delete(b));
+        assert_eq!(arena.try_remove(a), None);
+        assert!(arena.is_empty());
+
+        assert_eq!(arena.observer().0, [(true, 0, 10), (true, 1, 20), (false, 0, 10), (false, 1, 20)]);
+    }
+}
@@ -20,6 +20,81 @@
 //!
 //! If you want to access the raw backing `Arena`/`VacantEntry`, you still can,
 //! it is the only public field of each scoped arena/vacant entry.
+//!
+//! The easiest way to get a scoped arena is with `ScopedArena::with`, which
+//! opens a new scope and hands you an empty arena inside of it.
+//!
+//! ```rust
+//! use pui_arena::scoped::sparse::ScopedArena;
+//!
+//! ScopedArena::<_>::with(|mut arena| {
+//!     let a = arena.insert(10);
+//!     assert_eq!(arena[a], 10);
+//!     // `a` is branded, so it's always in bounds for `arena`
+//!     assert_eq!(unsafe { *arena.get_scoped_unchecked(a) }, 10);
+//! });
+//! ```
+//!
+//! If you don't want to structure your code around a closure, you can open a scope
+//! in the current block with [`pui_core::scope`], and build the arena from it.
+//! The arena and it's keys can't escape the block.
+//!
+//! ```rust
+//! use pui_arena::scoped::sparse::ScopedArena;
+//!
+//! pui_core::scope!(ident);
+//! let mut arena = ScopedArena::<_>::new(ident);
+//! let a = arena.insert(10);
+//! arena[a] += 1;
+//! assert_eq!(arena[a], 11);
+//! ```
+//!
+//! Each `scope!` has it's own brand, so keys from one can't be used with an arena from another
+//!
+//! ```rust,compile_fail
+//! use pui_arena::scoped::sparse::ScopedArena;
+//!
+//! pui_core::scope!(a);
+//! pui_core::scope!(b);
+//! let mut a = ScopedArena::<_>::new(a);
+//! let b = ScopedArena::<i32>::new(b);
+//! let key = a.insert(10);
+//! b.get(key);
+//! ```
+//!
+//! Nested scopes have distinct brands, so keys from an outer arena
+//! can't be used with an arena in an inner scope
+//!
+//! ```rust,compile_fail
+//! use pui_arena::scoped::sparse::ScopedArena;
+//!
+//! ScopedArena::<_>::with(|mut outer| {
+//!     let a = outer.insert(10);
+//!     outer.ident().scope_within(|inner| {
+//!         let inner = ScopedArena::<i32>::new(inner);
+//!         inner.get(a);
+//!     });
+//! });
+//! ```
+//!
+//! Scopes are lifetimes, so using a key with an arena from a different scope is
+//! reported as a lifetime error, not a type error. If you see errors like
+//! "borrowed data escapes outside of closure", or notes that "`ScopedKey<'scope, V>`
+//! is invariant over the parameter `'scope`", then a key from one scope is being
+//! used with an arena from another scope.
+//!
+//! Scoped arenas can't be cloned. Keys are trusted to be in bounds for every arena
+//! in their scope, so if a clone could share the scope, a key to a slot that was
+//! added to one clone would be used to access the other clone out of bounds.
+//!
+//! ```rust,compile_fail
+//! use pui_arena::scoped::sparse::ScopedArena;
+//!
+//! ScopedArena::<_>::with(|mut arena| {
+//!     let _ = arena.insert(10);
+//!     let fork = arena.clone();
+//! });
+//! ```
 
 use core::borrow::{Borrow, BorrowMut};
 
@@ -65,8 +140,18 @@ macro_rules! imp_scoped {
             pub fn new(ident: pui_core::scoped::Scoped<'scope>) -> Self {
                 Self(BaseArena::with_ident(ident))
             }
+            /// Call the given closure with a new arena in a new scope
+            ///
+            /// see [`Scoped::with`](pui_core::scoped::Scoped::with)
+            pub fn with<R, F: FnOnce(ScopedArena<'_, T, V>) -> R>(f: F) -> R {
+                pui_core::scoped::Scoped::with(|ident| f(ScopedArena::new(ident)))
+            }
             /// see [`ScopedArena::ident`](imp::Arena::ident)
             pub fn ident(&self) -> &pui_core::scoped::Scoped<'scope> { self.0.ident() }
+            /// The token of this arena's scope, see [`Scoped::token`](pui_core::scoped::Scoped::token)
+            ///
+            /// This can be used to build `pui_vec::Id`s that share this arena's scope
+            pub fn token(&self) -> pui_core::scoped::ScopedToken<'scope> { self.0.ident().token() }
             /// see [`ScopedArena::is_empty`](imp::Arena::is_empty)
             pub fn is_empty(&self) -> bool { self.0.is_empty() }
             /// see [`ScopedArena::len`](imp::Arena::is_empty)
@@ -81,8 +166,12 @@ macro_rules! imp_scoped {
             pub fn vacant_entry(&mut self) -> ScopedVacantEntry<'_, 'scope, T, V> { ScopedVacantEntry(self.0.vacant_entry()) }
             /// see [`ScopedArena::insert`](imp::Arena::insert)
             pub fn insert(&mut self, value: T) -> Key<'scope, V> { self.0.insert(value) }
+            /// see [`ScopedArena::insert_mut`](imp::Arena::insert_mut)
+            pub fn insert_mut(&mut self, value: T) -> (Key<'scope, V>, &mut T) { self.0.insert_mut(value) }
             /// see [`ScopedArena::contains`](imp::Arena::contains)
             pub fn contains(&self, key: Key<'scope, V>) -> bool { self.0.contains(key) }
+            /// see [`ScopedArena::classify`](imp::Arena::classify)
+            pub fn classify(&self, key: Key<'scope, V>) -> crate::KeyStatus { self.0.classify(key) }
             /// see [`ScopedArena::remove`](imp::Arena::remove)
             pub fn remove(&mut self, key: Key<'scope, V>) -> T { self.0.remove(key) }
             /// see [`ScopedArena::try_remove`](imp::Arena::try_remove)
@@ -99,6 +188,24 @@ macro_rules! imp_scoped {
             /// see [`ScopedArena::get_unchecked_mut`](imp::Arena::get_unchecked_mut)
             #[allow(clippy::missing_safety_doc)]
             pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T { self.0.get_unchecked_mut(index) }
+            /// Return a shared reference to the value associated with the given key,
+            /// without checking the key's version.
+            ///
+            /// The key is branded by the scope, so it is always in bounds
+            ///
+            /// # Safety
+            ///
+            /// The key's slot must currently be occupied at this key's version
+            pub unsafe fn get_scoped_unchecked(&self, key: Key<'scope, V>) -> &T { self.0.get_unchecked(key.0.id().get()) }
+            /// Return a unique reference to the value associated with the given key,
+            /// without checking the key's version.
+            ///
+            /// The key is branded by the scope, so it is always in bounds
+            ///
+            /// # Safety
+            ///
+            /// The key's slot must currently be occupied at this key's version
+            pub unsafe fn get_scoped_unchecked_mut(&mut self, key: Key<'scope, V>) -> &mut T { self.0.get_unchecked_mut(key.0.id().get()) }
             /// see [`ScopedArena::delete_all`](imp::Arena::delete_all)
             pub fn delete_all(&mut self) { self.0.delete_all() }
             /// see [`ScopedArena::retain`](imp::Arena::retain)
@@ -207,9 +314,9 @@ macro_rules! imp_scoped {
             use crate::base::dense as imp;
 
             /// Returned from [`ScopedArena::iter`]
-            pub type Iter<'a, T> = core::slice::Iter<'a, T>;
+            pub type Iter<'a, T> = imp::Iter<'a, T>;
             /// Returned from [`ScopedArena::iter_mut`]
-            pub type IterMut<'a, T> = core::slice::IterMut<'a, T>;
+            pub type IterMut<'a, T> = imp::IterMut<'a, T>;
             /// Returned from [`ScopedArena::into_iter`]
             pub type IntoIter<T> = std::vec::IntoIter<T>;
 
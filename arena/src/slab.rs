@@ -16,6 +16,18 @@
 //!
 //! If you want to access the raw backing `Arena`/`VacantEntry`, you still can,
 //! it is the only public field of each slotmap/vacant entry.
+//!
+//! `Slab` follows the semantics of `slab` v0.4, `len` is the number of values,
+//! `capacity` is the number of values that can be held without reallocating,
+//! `VacantEntry::key` is the key that the value will be inserted at, and
+//! iterating over a `Slab` by value or by reference yields key-value pairs.
+//!
+//! There are a few intentional deviations from `slab`,
+//!
+//! * `iter` and `iter_mut` only yield values, use `entries` and `entries_mut` for key-value pairs
+//! * keys are only guaranteed to be reused in the same order as `slab` for [`sparse::Slab`]
+//!     and [`dense::Slab`]
+//! * `compact` and `shrink_to_fit` aren't provided
 
 macro_rules! imp_slab {
     (
@@ -40,6 +52,8 @@ macro_rules! imp_slab {
         pub type EntriesMut<'a, T> = imp::EntriesMut<'a, T, (), Unversioned, usize>;
         /// Returned from [`Slab::into_entries`]
         pub type IntoEntries<T> = imp::IntoEntries<T, (), Unversioned, usize>;
+        /// Returned from [`Slab::into_iter`]
+        pub type IntoIter<T> = IntoEntries<T>;
 
         impl<T> VacantEntry<'_, T> {
             /// see [`VacantEntry::key`](imp::VacantEntry::key)
@@ -70,8 +84,12 @@ macro_rules! imp_slab {
             pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> { VacantEntry(self.0.vacant_entry()) }
             /// see [`Arena::insert`](imp::Arena::insert)
             pub fn insert(&mut self, value: T) -> Key { self.0.insert(value) }
+            /// see [`Arena::insert_mut`](imp::Arena::insert_mut)
+            pub fn insert_mut(&mut self, value: T) -> (Key, &mut T) { self.0.insert_mut(value) }
             /// see [`Arena::contains`](imp::Arena::contains)
             pub fn contains(&self, key: Key) -> bool { self.0.contains(key) }
+            /// see [`Arena::classify`](imp::Arena::classify)
+            pub fn classify(&self, key: Key) -> crate::KeyStatus { self.0.classify(key) }
             /// see [`Arena::remove`](imp::Arena::remove)
             pub fn remove(&mut self, key: Key) -> T { self.0.remove(key) }
             /// see [`Arena::try_remove`](imp::Arena::try_remove)
@@ -82,6 +100,25 @@ macro_rules! imp_slab {
             pub fn get(&self, key: Key) -> Option<&T> { self.0.get(key) }
             /// see [`Arena::get_mut`](imp::Arena::get_mut)
             pub fn get_mut(&mut self, key: Key) -> Option<&mut T> { self.0.get_mut(key) }
+            /// Return unique references to the values associated with `key1` and `key2`,
+            /// see [`Arena::get_disjoint_mut`](imp::Arena::get_disjoint_mut)
+            ///
+            /// # Panics
+            ///
+            /// If `key1 == key2`
+            pub fn get2_mut(&mut self, key1: Key, key2: Key) -> Option<(&mut T, &mut T)> {
+                assert!(key1 != key2);
+                let [a, b] = self.0.get_disjoint_mut([key1, key2])?;
+                Some((a, b))
+            }
+            /// Get the key of a value in the slab, see [`Arena::key_of`](imp::Arena::key_of)
+            ///
+            /// # Panics
+            ///
+            /// If `present_element` isn't in the slab
+            pub fn key_of(&self, present_element: &T) -> Key {
+                self.0.key_of(present_element).expect("The reference points to a value outside this slab")
+            }
             /// see [`Arena::get_unchecked`](imp::Arena::get_unchecked)
             #[allow(clippy::missing_safety_doc)]
             pub unsafe fn get_unchecked(&self, index: usize) -> &T { self.0.get_unchecked(index) }
@@ -112,9 +149,23 @@ macro_rules! imp_slab {
 
         impl<T> IntoIterator for Slab<T> {
             type IntoIter = IntoIter<T>;
-            type Item = T;
+            type Item = (Key, T);
+
+            fn into_iter(self) -> Self::IntoIter { self.0.into_entries() }
+        }
+
+        impl<'a, T> IntoIterator for &'a Slab<T> {
+            type IntoIter = Entries<'a, T>;
+            type Item = (Key, &'a T);
+
+            fn into_iter(self) -> Self::IntoIter { self.0.entries() }
+        }
+
+        impl<'a, T> IntoIterator for &'a mut Slab<T> {
+            type IntoIter = EntriesMut<'a, T>;
+            type Item = (Key, &'a mut T);
 
-            fn into_iter(self) -> Self::IntoIter { self.0.into_iter() }
+            fn into_iter(self) -> Self::IntoIter { self.0.entries_mut() }
         }
 
         impl<T> Index<Key> for Slab<T> {
@@ -141,11 +192,9 @@ pub mod dense {
     };
 
     /// Returned from [`Slab::iter`]
-    pub type Iter<'a, T> = core::slice::Iter<'a, T>;
+    pub type Iter<'a, T> = imp::Iter<'a, T>;
     /// Returned from [`Slab::iter_mut`]
-    pub type IterMut<'a, T> = core::slice::IterMut<'a, T>;
-    /// Returned from [`Slab::into_iter`]
-    pub type IntoIter<T> = std::vec::IntoIter<T>;
+    pub type IterMut<'a, T> = imp::IterMut<'a, T>;
 
     /// Returned from [`Slab::drain`]
     pub type Drain<'a, T> = imp::Drain<'a, T, (), Unversioned>;
@@ -177,8 +226,6 @@ pub mod hop {
     pub type Iter<'a, T> = imp::Iter<'a, T, Unversioned>;
     /// Returned from [`Slab::iter_mut`]
     pub type IterMut<'a, T> = imp::IterMut<'a, T, Unversioned>;
-    /// Returned from [`Slab::into_iter`]
-    pub type IntoIter<T> = imp::IntoIter<T, Unversioned>;
 
     /// Returned from [`Slab::drain`]
     pub type Drain<'a, T> = imp::Drain<'a, T, Unversioned>;
@@ -210,8 +257,6 @@ pub mod sparse {
     pub type Iter<'a, T> = imp::Iter<'a, T, Unversioned>;
     /// Returned from [`Slab::iter_mut`]
     pub type IterMut<'a, T> = imp::IterMut<'a, T, Unversioned>;
-    /// Returned from [`Slab::into_iter`]
-    pub type IntoIter<T> = imp::IntoIter<T, Unversioned>;
 
     /// Returned from [`Slab::drain`]
     pub type Drain<'a, T> = imp::Drain<'a, T, Unversioned>;
@@ -70,8 +70,12 @@ macro_rules! imp_slot_map {
             pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> { VacantEntry(self.0.vacant_entry()) }
             /// see [`Arena::insert`](imp::Arena::insert)
             pub fn insert(&mut self, value: T) -> Key { self.0.insert(value) }
+            /// see [`Arena::insert_mut`](imp::Arena::insert_mut)
+            pub fn insert_mut(&mut self, value: T) -> (Key, &mut T) { self.0.insert_mut(value) }
             /// see [`Arena::contains`](imp::Arena::contains)
             pub fn contains(&self, key: Key) -> bool { self.0.contains(key) }
+            /// see [`Arena::classify`](imp::Arena::classify)
+            pub fn classify(&self, key: Key) -> crate::KeyStatus { self.0.classify(key) }
             /// see [`Arena::remove`](imp::Arena::remove)
             pub fn remove(&mut self, key: Key) -> T { self.0.remove(key) }
             /// see [`Arena::try_remove`](imp::Arena::try_remove)
@@ -141,9 +145,9 @@ pub mod dense {
     };
 
     /// Returned from [`SlotMap::iter`]
-    pub type Iter<'a, T> = core::slice::Iter<'a, T>;
+    pub type Iter<'a, T> = imp::Iter<'a, T>;
     /// Returned from [`SlotMap::iter_mut`]
-    pub type IterMut<'a, T> = core::slice::IterMut<'a, T>;
+    pub type IterMut<'a, T> = imp::IterMut<'a, T>;
     /// Returned from [`SlotMap::into_iter`]
     pub type IntoIter<T> = std::vec::IntoIter<T>;
 
@@ -1,5 +1,8 @@
 //! The versioning strategy, see [`Version`] for details
 
+use core::{any::Any, cmp::Ordering, num::NonZeroU32};
+use std::boxed::Box;
+
 /// The versioning strategy
 ///
 /// # Slot Exhaustion
@@ -43,6 +46,25 @@
 /// in some way, because there are never going to be an infinite number of versions. `pui-arena`
 /// handles this for you by "leaking" slots with exhausted versions. These slots will not
 /// be reused, but will be deallocated once the `Arena` drops.
+///
+/// # Ordering
+///
+/// Saved versions can be ordered by generation with [`save_cmp`](Version::save_cmp),
+/// so a key from a reused slot compares greater than the stale keys for that same slot.
+/// Since slots with exhausted versions are never reused, versions never wrap around.
+/// But `drain_reset` and `restore_structure` start the versions of slots over, so
+/// this only holds for keys that were created after the last reset.
+///
+/// ```
+/// # use pui_arena::{Key, version::{DefaultVersion, Version}};
+/// # use core::cmp::Ordering;
+/// # let mut arena = pui_arena::base::sparse::Arena::<_, (), DefaultVersion>::INIT;
+/// let a: Key<usize> = arena.insert(0);
+/// arena.remove(a);
+/// let b: Key<usize> = arena.insert(10);
+/// assert_eq!(a.id(), b.id());
+/// assert_eq!(DefaultVersion::save_cmp(a.version(), b.version()), Some(Ordering::Less));
+/// ```
 pub unsafe trait Version: Copy {
     /// Represents a full version
     type Save: Copy;
@@ -86,6 +108,145 @@ pub unsafe trait Version: Copy {
     /// and may not be true if there was a call to `mark_empty` in since the
     /// save was created.
     fn equals_saved(self, saved: Self::Save) -> bool;
+
+    /// Compare two saved versions of the same slot by generation, see [Ordering](Version#ordering)
+    ///
+    /// Returns `None` if this versioning strategy doesn't order it's versions, which is the default
+    fn save_cmp(_: &Self::Save, _: &Self::Save) -> Option<Ordering> { None }
+}
+
+/// A [`Version`] that can be rebuilt from a saved version
+pub trait RestorableVersion: Version {
+    /// Get the full version that was saved as `saved`
+    ///
+    /// This must return a full version, such that `version.equals_saved(saved)`
+    fn from_saved(saved: Self::Save) -> Self;
+}
+
+/// A [`Version`] with an integer encoding for it's saved versions,
+/// for example to store keys on disk
+///
+/// The encodings of the versions in this crate are stable:
+///
+/// * [`DefaultVersion`] and [`NonMaxVersion`] - the version as a `u32`, which is always even
+/// * [`TinyVersion`] - the version as a `u8`, which is always even
+/// * [`Unversioned`] - always `0`
+/// * [`Tagged<V, BITS>`](Tagged) - the tag in the high `BITS` bits, and the inner version in the other bits
+pub trait VersionBits: Version {
+    /// Encode a saved version as an integer
+    fn save_to_bits(save: &Self::Save) -> u64;
+
+    /// Decode a saved version from [`save_to_bits`](VersionBits::save_to_bits)
+    ///
+    /// Returns `None` if `bits` isn't a valid encoding for a saved version
+    fn save_from_bits(bits: u64) -> Option<Self::Save>;
+}
+
+/// Decode a `u32` full version, which is always even
+fn even_u32(bits: u64) -> Option<u32> {
+    match core::convert::TryFrom::try_from(bits) {
+        Ok(version) if version & 1 == 0 => Some(version),
+        _ => None,
+    }
+}
+
+/// Check if a slot with the version `version` was used after it had the version `old`,
+/// see `Arena::is_reused`
+pub(crate) fn is_reused<V: Version>(version: V, old: V::Save) -> bool {
+    if version.is_full() {
+        !version.equals_saved(old)
+    } else if version.is_exhausted() {
+        true
+    } else {
+        // the slot was used after `old` if the next value won't get the version `old`
+        !unsafe { version.mark_full() }.equals_saved(old)
+    }
+}
+
+/// An object safe view of a [`Version`], for tools that inspect arenas
+/// with different versioning strategies
+///
+/// This is implemented for all `'static` versions, and arenas can
+/// produce one for each slot with `dyn_version`.
+///
+/// ```
+/// # use pui_arena::version::{DefaultVersion, DynVersion};
+/// let mut arena = pui_arena::base::sparse::Arena::<_, (), DefaultVersion>::INIT;
+/// let key: usize = arena.insert(10);
+/// let version = arena.dyn_version(key).unwrap();
+/// assert!(version.full());
+/// assert!(version.save_boxed().unwrap().is::<<DefaultVersion as pui_arena::version::Version>::Save>());
+/// ```
+///
+/// The methods are named differently from [`Version`]'s, so that both traits can be in scope
+pub trait DynVersion {
+    /// Check if the version is full
+    fn full(&self) -> bool;
+
+    /// Check if the version is exhausted
+    fn exhausted(&self) -> bool;
+
+    /// Save the current version, or `None` if the version isn't full
+    fn save_boxed(&self) -> Option<Box<dyn Any>>;
+
+    /// The name of the versioning strategy
+    fn type_name(&self) -> &'static str;
+}
+
+impl<V: Version + 'static> DynVersion for V
+where
+    V::Save: 'static,
+{
+    fn full(&self) -> bool { self.is_full() }
+
+    fn exhausted(&self) -> bool { self.is_exhausted() }
+
+    fn save_boxed(&self) -> Option<Box<dyn Any>> {
+        if self.is_full() {
+            Some(Box::new(unsafe { self.save() }))
+        } else {
+            None
+        }
+    }
+
+    fn type_name(&self) -> &'static str { core::any::type_name::<V>() }
+}
+
+/// What an arena does when it needs to grow to insert a value, but
+/// some of it's slots are exhausted
+///
+/// Unbounded growth with many exhausted slots is usually a sign
+/// that keys are being used after their values were removed. Set
+/// this with `set_on_exhaustion` to catch this early.
+///
+/// ```
+/// # use pui_arena::version::ExhaustionPolicy;
+/// let mut arena = pui_arena::base::sparse::Arena::new();
+/// assert_eq!(arena.on_exhaustion(), ExhaustionPolicy::Retire);
+/// arena.set_on_exhaustion(ExhaustionPolicy::Panic);
+/// let _: usize = arena.insert(0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExhaustionPolicy {
+    /// Panic instead of growing the arena, if it only needs to grow because of exhausted slots
+    Panic,
+    /// Retire the exhausted slots and grow the arena, this is the default
+    Retire,
+}
+
+impl ExhaustionPolicy {
+    /// Check if an arena with `slots` slots, that holds `len` values, may grow
+    ///
+    /// Arenas only grow if none of their slots are vacant, so all the
+    /// slots that don't hold a value are exhausted.
+    pub(crate) fn check(self, slots: usize, len: usize) {
+        if self == Self::Panic && slots > len {
+            panic!(
+                "Tried to grow an arena with {} exhausted slots, keys may be used after their values were removed",
+                slots - len
+            )
+        }
+    }
 }
 
 /// The default versioning strategy, that's backed by a [`u32`], that avoids the
@@ -95,7 +256,7 @@ pub unsafe trait Version: Copy {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DefaultVersion(u32);
 /// `<DefaultVersion as Version>::Save`
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SavedDefaultVersion(u32);
 
 unsafe impl Version for DefaultVersion {
@@ -120,6 +281,71 @@ unsafe impl Version for DefaultVersion {
     unsafe fn save(self) -> Self::Save { SavedDefaultVersion(self.0) }
 
     fn equals_saved(self, saved: Self::Save) -> bool { self.0 == saved.0 }
+
+    fn save_cmp(a: &Self::Save, b: &Self::Save) -> Option<Ordering> { Some(a.cmp(b)) }
+}
+
+impl RestorableVersion for DefaultVersion {
+    fn from_saved(saved: Self::Save) -> Self { Self(saved.0) }
+}
+
+impl VersionBits for DefaultVersion {
+    fn save_to_bits(save: &Self::Save) -> u64 { save.0.into() }
+
+    fn save_from_bits(bits: u64) -> Option<Self::Save> { even_u32(bits).map(SavedDefaultVersion) }
+}
+
+/// A versioning strategy, that's backed by a [`u32`], that avoids the
+/// [`ABA problem`](https://en.wikipedia.org/wiki/ABA_problem)
+///
+/// This behaves exactly like [`DefaultVersion`], but the saved version is never
+/// `u32::MAX`, so `Option<Key<_, SavedNonMaxVersion>>` is the same size as
+/// `Key<_, SavedNonMaxVersion>`.
+///
+/// This can track up to 2^31 insertion-deletion pairs before exhaustion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonMaxVersion(u32);
+/// `<NonMaxVersion as Version>::Save`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SavedNonMaxVersion(NonZeroU32);
+
+unsafe impl Version for NonMaxVersion {
+    type Save = SavedNonMaxVersion;
+
+    const EMPTY: Self = Self(1);
+
+    unsafe fn mark_empty(self) -> Result<Self, Self> {
+        let next = Self(self.0 | 1);
+        match self.0.checked_add(2) {
+            Some(_) => Ok(next),
+            None => Err(next),
+        }
+    }
+
+    fn is_exhausted(&self) -> bool { self.0 == u32::MAX }
+
+    unsafe fn mark_full(self) -> Self { Self(self.0.wrapping_add(1)) }
+
+    fn is_full(self) -> bool { self.0 & 1 == 0 }
+
+    // full versions are even, so they are at most `u32::MAX - 1`, and adding one can't overflow
+    unsafe fn save(self) -> Self::Save { SavedNonMaxVersion(NonZeroU32::new_unchecked(self.0 + 1)) }
+
+    fn equals_saved(self, saved: Self::Save) -> bool { self.0.wrapping_add(1) == saved.0.get() }
+
+    fn save_cmp(a: &Self::Save, b: &Self::Save) -> Option<Ordering> { Some(a.cmp(b)) }
+}
+
+impl RestorableVersion for NonMaxVersion {
+    fn from_saved(saved: Self::Save) -> Self { Self(saved.0.get() - 1) }
+}
+
+impl VersionBits for NonMaxVersion {
+    fn save_to_bits(save: &Self::Save) -> u64 { (save.0.get() - 1).into() }
+
+    fn save_from_bits(bits: u64) -> Option<Self::Save> {
+        even_u32(bits).map(|version| SavedNonMaxVersion(unsafe { NonZeroU32::new_unchecked(version + 1) }))
+    }
 }
 
 /// A small versioning strategy, that's backed by a [`u8`], that avoids the
@@ -129,7 +355,7 @@ unsafe impl Version for DefaultVersion {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TinyVersion(u8);
 /// `<TinyVersion as Version>::Save`
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct SavedTinyVersion(u8);
 
 unsafe impl Version for TinyVersion {
@@ -149,11 +375,28 @@ unsafe impl Version for TinyVersion {
 
     fn is_exhausted(&self) -> bool { self.0 == u8::MAX }
 
-    fn is_full(self) -> bool { self.0 & 1 != 0 }
+    fn is_full(self) -> bool { self.0 & 1 == 0 }
 
     unsafe fn save(self) -> Self::Save { SavedTinyVersion(self.0) }
 
     fn equals_saved(self, saved: Self::Save) -> bool { self.0 == saved.0 }
+
+    fn save_cmp(a: &Self::Save, b: &Self::Save) -> Option<Ordering> { Some(a.cmp(b)) }
+}
+
+impl RestorableVersion for TinyVersion {
+    fn from_saved(saved: Self::Save) -> Self { Self(saved.0) }
+}
+
+impl VersionBits for TinyVersion {
+    fn save_to_bits(save: &Self::Save) -> u64 { save.0.into() }
+
+    fn save_from_bits(bits: u64) -> Option<Self::Save> {
+        match core::convert::TryFrom::try_from(bits) {
+            Ok(version) if version & 1 == 0 => Some(SavedTinyVersion(version)),
+            _ => None,
+        }
+    }
 }
 
 /// A versioning strategy that doesn't actually track versions,
@@ -167,7 +410,7 @@ pub enum Unversioned {
     Full,
 }
 /// `<UnversionedFull as Version>::Save`
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct UnversionedFull(());
 
 unsafe impl Version for Unversioned {
@@ -186,4 +429,359 @@ unsafe impl Version for Unversioned {
     unsafe fn save(self) -> Self::Save { UnversionedFull(()) }
 
     fn equals_saved(self, UnversionedFull(()): Self::Save) -> bool { self.is_full() }
+
+    fn save_cmp(UnversionedFull(()): &Self::Save, UnversionedFull(()): &Self::Save) -> Option<Ordering> {
+        Some(Ordering::Equal)
+    }
+}
+
+impl RestorableVersion for Unversioned {
+    fn from_saved(UnversionedFull(()): Self::Save) -> Self { Self::Full }
+}
+
+impl VersionBits for Unversioned {
+    fn save_to_bits(UnversionedFull(()): &Self::Save) -> u64 { 0 }
+
+    fn save_from_bits(bits: u64) -> Option<Self::Save> {
+        match bits {
+            0 => Some(UnversionedFull(())),
+            _ => None,
+        }
+    }
+}
+
+/// A versioning strategy that pairs another versioning strategy with a
+/// `BITS`-wide tag, so keys also record which category their value belongs to
+///
+/// The tag is chosen on insertion with `insert_tagged`, and cleared when the
+/// value is removed. A key only matches its slot if both the inner version and
+/// the tag match, so a key with the wrong tag will never access the value.
+/// Plain `insert` uses the tag `0`.
+///
+/// # Keys from other arenas
+///
+/// Versions are created and updated without any access to their arena, so a version
+/// can't mix a per-arena salt into it's saves by itself. If you have many arenas with
+/// the identifier `()`, you can give each arena it's own tag, and always insert with it.
+/// Then a key from one arena will usually not match a slot in another arena.
+///
+/// ```
+/// # use pui_arena::{Key, version::{DefaultVersion, Tagged, SavedTagged, SavedDefaultVersion}};
+/// type Arena<T> = pui_arena::base::sparse::Arena<T, (), Tagged<DefaultVersion, 32>>;
+/// type SaltedKey = Key<usize, SavedTagged<SavedDefaultVersion, 32>>;
+///
+/// let (mut a, salt_a) = (Arena::INIT, 0x5eed_0001);
+/// let (mut b, salt_b) = (Arena::INIT, 0x5eed_0002);
+///
+/// let key_a: SaltedKey = a.insert_tagged(salt_a, 'a');
+/// let key_b: SaltedKey = b.insert_tagged(salt_b, 'b');
+///
+/// assert_eq!(key_a.id(), key_b.id());
+/// assert_eq!(a.get(key_a), Some(&'a'));
+/// assert_eq!(b.get(key_a), None);
+/// ```
+///
+/// This only catches mistakes with a high probability, to rule them out entirely
+/// use a unique identifier like `pui_core::dynamic::Dynamic` instead of `()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tagged<V, const BITS: u32> {
+    version: V,
+    tag: u32,
+}
+/// `<Tagged<V, BITS> as Version>::Save`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SavedTagged<S, const BITS: u32> {
+    version: S,
+    tag: u32,
+}
+
+impl<V, const BITS: u32> Tagged<V, BITS> {
+    /// The tag of this version
+    pub fn tag(&self) -> u32 { self.tag }
+
+    /// Replace the tag of this version
+    ///
+    /// # Panics
+    ///
+    /// If `tag` doesn't fit in `BITS` bits
+    pub(crate) fn with_tag(self, tag: u32) -> Self {
+        assert!(
+            matches!(tag.checked_shr(BITS), None | Some(0)),
+            "tag {} doesn't fit in {} bits",
+            tag,
+            BITS
+        );
+        Self { tag, ..self }
+    }
+}
+
+impl<S, const BITS: u32> SavedTagged<S, BITS> {
+    /// The tag of this saved version
+    pub fn tag(&self) -> u32 { self.tag }
+
+    /// The saved inner version
+    pub fn version(&self) -> &S { &self.version }
+}
+
+unsafe impl<V: Version, const BITS: u32> Version for Tagged<V, BITS> {
+    type Save = SavedTagged<V::Save, BITS>;
+
+    const EMPTY: Self = Self { version: V::EMPTY, tag: 0 };
+
+    unsafe fn mark_empty(self) -> Result<Self, Self> {
+        match self.version.mark_empty() {
+            Ok(version) => Ok(Self { version, tag: 0 }),
+            Err(version) => Err(Self { version, tag: 0 }),
+        }
+    }
+
+    unsafe fn mark_full(self) -> Self {
+        Self {
+            version: self.version.mark_full(),
+            tag: self.tag,
+        }
+    }
+
+    fn is_exhausted(&self) -> bool { self.version.is_exhausted() }
+
+    fn is_full(self) -> bool { self.version.is_full() }
+
+    unsafe fn save(self) -> Self::Save {
+        SavedTagged {
+            version: self.version.save(),
+            tag: self.tag,
+        }
+    }
+
+    fn equals_saved(self, saved: Self::Save) -> bool {
+        self.tag == saved.tag && self.version.equals_saved(saved.version)
+    }
+
+    /// The tags are ignored, since they don't change the generation
+    fn save_cmp(a: &Self::Save, b: &Self::Save) -> Option<Ordering> { V::save_cmp(&a.version, &b.version) }
+}
+
+impl<V: RestorableVersion, const BITS: u32> RestorableVersion for Tagged<V, BITS> {
+    fn from_saved(saved: Self::Save) -> Self {
+        Self {
+            version: V::from_saved(saved.version),
+            tag: saved.tag,
+        }
+    }
+}
+
+impl<V: VersionBits, const BITS: u32> VersionBits for Tagged<V, BITS> {
+    /// # Panics
+    ///
+    /// If the inner version's encoding doesn't fit in `64 - BITS` bits
+    fn save_to_bits(save: &Self::Save) -> u64 {
+        let bits = V::save_to_bits(&save.version);
+        assert!(
+            matches!(bits.checked_shr(64 - BITS), None | Some(0)),
+            "the inner version doesn't fit in {} bits",
+            64 - BITS
+        );
+        bits | u64::from(save.tag).checked_shl(64 - BITS).unwrap_or(0)
+    }
+
+    fn save_from_bits(bits: u64) -> Option<Self::Save> {
+        let tag = bits.checked_shr(64 - BITS).unwrap_or(0);
+        let mask = u64::MAX.checked_shr(BITS).unwrap_or(0);
+        Some(SavedTagged {
+            version: V::save_from_bits(bits & mask)?,
+            tag: core::convert::TryFrom::try_from(tag).ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Key;
+    use core::mem::size_of;
+
+    #[test]
+    fn non_max_niche() {
+        assert_eq!(size_of::<Option<Key<usize, SavedNonMaxVersion>>>(), size_of::<Key<usize, SavedNonMaxVersion>>());
+        assert_eq!(size_of::<Option<SavedNonMaxVersion>>(), size_of::<u32>());
+    }
+
+    #[test]
+    fn non_max_version() {
+        let mut arena = crate::base::sparse::Arena::<_, (), NonMaxVersion>::INIT;
+        let a: Key<usize, SavedNonMaxVersion> = arena.insert(0);
+        arena.remove(a);
+        let b: Key<usize, SavedNonMaxVersion> = arena.insert(10);
+        assert_eq!(arena.get(a), None);
+        assert_eq!(arena[b], 10);
+        assert!(a.version() < b.version());
+    }
+
+    #[test]
+    fn tiny_version_exhaustion() {
+        let mut arena = crate::base::sparse::Arena::<_, (), TinyVersion>::INIT;
+        let first: Key<usize, SavedTinyVersion> = arena.insert(0);
+        arena.remove(first);
+
+        // the full versions are the even numbers from 2 to 254
+        for i in 1..127 {
+            let key: Key<usize, SavedTinyVersion> = arena.insert(i);
+            assert_eq!(key.id(), first.id());
+            assert_eq!(arena[key], i);
+            arena.remove(key);
+            assert_eq!(arena.get(key), None);
+        }
+
+        assert_eq!(arena.exhausted_slots(), 1);
+        assert_eq!(arena.get(*first.id()), None);
+        let key: Key<usize, SavedTinyVersion> = arena.insert(127);
+        assert_ne!(key.id(), first.id());
+        assert_eq!(arena[key], 127);
+    }
+
+    #[test]
+    fn tagged() {
+        type Tagged = super::Tagged<DefaultVersion, 4>;
+        type TaggedKey = Key<usize, SavedTagged<SavedDefaultVersion, 4>>;
+
+        let mut arena = crate::base::sparse::Arena::<_, (), Tagged>::INIT;
+        let a: TaggedKey = arena.insert_tagged(3, 'a');
+        let b: TaggedKey = arena.insert('b');
+        assert_eq!(a.version().tag(), 3);
+        assert_eq!(b.version().tag(), 0);
+        assert_eq!(arena[a], 'a');
+
+        let wrong_tag = Key::new(*a.id(), SavedTagged { tag: 2, ..*a.version() });
+        assert_eq!(arena.get(wrong_tag), None);
+
+        arena.remove(a);
+        let c: TaggedKey = arena.insert('c');
+        assert_eq!(c.id(), a.id());
+        assert_eq!(c.version().tag(), 0);
+        assert_eq!(arena.get(a), None);
+    }
+
+    #[test]
+    fn panic_on_exhaustion_allows_growth() {
+        let mut sparse = crate::base::sparse::Arena::new();
+        let mut hop = crate::base::hop::Arena::new();
+        sparse.set_on_exhaustion(ExhaustionPolicy::Panic);
+        hop.set_on_exhaustion(ExhaustionPolicy::Panic);
+
+        for i in 0..10 {
+            let _: usize = sparse.insert(i);
+            let _: usize = hop.insert(i);
+        }
+
+        sparse.remove(3);
+        hop.remove(3);
+        let _: usize = sparse.insert(10);
+        let _: usize = hop.insert(10);
+        assert_eq!((sparse.len(), hop.len()), (10, 10));
+    }
+
+    #[test]
+    fn save_cmp() {
+        type Tagged = super::Tagged<DefaultVersion, 4>;
+
+        let mut arena = crate::base::sparse::Arena::<_, (), Tagged>::INIT;
+        let a: Key<usize, SavedTagged<SavedDefaultVersion, 4>> = arena.insert_tagged(3, 'a');
+        arena.remove(a);
+        let b: Key<usize, SavedTagged<SavedDefaultVersion, 4>> = arena.insert('b');
+        assert_eq!(Tagged::save_cmp(a.version(), b.version()), Some(Ordering::Less));
+        assert_eq!(Tagged::save_cmp(b.version(), b.version()), Some(Ordering::Equal));
+        assert_eq!(
+            DefaultVersion::save_cmp(b.version().version(), a.version().version()),
+            Some(Ordering::Greater)
+        );
+
+        let full = unsafe { Unversioned::Full.save() };
+        assert_eq!(Unversioned::save_cmp(&full, &full), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn save_bits() {
+        fn round_trip<V: VersionBits>() -> (u64, u64)
+        where
+            V::Save: core::fmt::Debug + PartialEq,
+        {
+            let mut arena = crate::base::sparse::Arena::<(), (), V>::INIT;
+            let a: Key<usize, V::Save> = arena.insert(());
+            arena.remove(a);
+            let b: Key<usize, V::Save> = arena.insert(());
+            for key in [a, b] {
+                assert_eq!(V::save_from_bits(V::save_to_bits(key.version())), Some(*key.version()));
+            }
+            (V::save_to_bits(a.version()), V::save_to_bits(b.version()))
+        }
+
+        assert_eq!(round_trip::<DefaultVersion>(), (2, 4));
+        assert_eq!(round_trip::<NonMaxVersion>(), (2, 4));
+        assert_eq!(round_trip::<TinyVersion>(), (2, 4));
+        assert_eq!(round_trip::<Unversioned>(), (0, 0));
+
+        let mut arena = crate::base::sparse::Arena::<_, (), Tagged<DefaultVersion, 4>>::INIT;
+        let a: Key<usize, SavedTagged<SavedDefaultVersion, 4>> = arena.insert_tagged(3, ());
+        let bits = Tagged::<DefaultVersion, 4>::save_to_bits(a.version());
+        assert_eq!(bits, 3 << 60 | 2);
+        assert_eq!(Tagged::<DefaultVersion, 4>::save_from_bits(bits), Some(*a.version()));
+
+        // odd versions are empty, and values that are too large can't be produced by `save`
+        assert_eq!(DefaultVersion::save_from_bits(1), None);
+        assert_eq!(DefaultVersion::save_from_bits(1 << 32), None);
+        assert_eq!(NonMaxVersion::save_from_bits(u32::MAX.into()), None);
+        assert_eq!(TinyVersion::save_from_bits(255), None);
+        assert_eq!(TinyVersion::save_from_bits(256), None);
+        assert_eq!(Unversioned::save_from_bits(1), None);
+        assert_eq!(Tagged::<DefaultVersion, 4>::save_from_bits(1 << 59), None);
+    }
+
+    #[test]
+    fn tiny_version_index() {
+        let mut arena = crate::base::sparse::Arena::<_, (), TinyVersion>::INIT;
+        let a: usize = arena.insert(0);
+        assert_eq!(arena.get(a), Some(&0));
+        arena.remove(a);
+        assert_eq!(arena.get(a), None);
+    }
+
+    #[test]
+    #[cfg(feature = "test-util")]
+    fn force_version() {
+        let last = DefaultVersion::save_from_bits(u64::from(u32::MAX - 1)).unwrap();
+
+        let mut arena = crate::base::sparse::Arena::<_, (), DefaultVersion>::INIT;
+        let a: Key<usize> = arena.insert(0);
+        arena.force_version(*a.id(), last);
+        assert_eq!(arena.get(a), None);
+
+        let a = Key::new(*a.id(), last);
+        assert_eq!(arena.remove(a), 0);
+        assert_eq!(arena.exhausted_slots(), 1);
+        let b: Key<usize> = arena.insert(1);
+        assert_ne!(a.id(), b.id());
+
+        let mut arena = crate::base::hop::Arena::<_, (), DefaultVersion>::new();
+        let a: Key<usize> = arena.insert(0);
+        arena.force_version(*a.id(), last);
+        arena.remove(Key::new(*a.id(), last));
+        assert_eq!(arena.exhausted_slots(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(feature = "test-util")]
+    fn force_vacant_version() {
+        let mut arena = crate::base::dense::Arena::<_, (), DefaultVersion>::new();
+        let a: Key<usize> = arena.insert(0);
+        arena.remove(a);
+        arena.force_version(*a.id(), *a.version());
+    }
+
+    #[test]
+    #[should_panic]
+    fn tag_too_wide() {
+        let mut arena = crate::base::sparse::Arena::<_, (), Tagged<DefaultVersion, 4>>::INIT;
+        let _: usize = arena.insert_tagged(16, ());
+    }
 }
@@ -0,0 +1,141 @@
+//! Arenas with a fixed key type, see [`WithKey`] for details
+//!
+//! Most arena methods are generic over the key type, which means that you
+//! often need a turbofish or a type annotation to pick which key to use.
+//! Wrapping an arena with `Arena::with_key` fixes the key type once, so
+//! that `insert`, `get`, `remove` and friends don't need any annotations.
+//!
+//! ```
+//! use pui_arena::{base::sparse::Arena, Key};
+//!
+//! let mut arena = Arena::<_, ()>::new().with_key::<Key<usize>>();
+//! let a = arena.insert(10);
+//! let b = arena.insert(20);
+//! assert_eq!(arena.remove(a), 10);
+//! assert_eq!(arena[b], 20);
+//! ```
+
+use core::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut, Index, IndexMut},
+};
+
+use crate::{version::Version, BuildArenaKey};
+
+/// An arena whose methods always use the key type `K`
+///
+/// `WithKey` dereferences to the backing arena, so every other
+/// method is still available.
+pub struct WithKey<A, K> {
+    arena: A,
+    key: PhantomData<fn() -> K>,
+}
+
+impl<A, K> WithKey<A, K> {
+    /// Fix the key type of the given arena
+    pub const fn new(arena: A) -> Self {
+        Self {
+            arena,
+            key: PhantomData,
+        }
+    }
+
+    /// Get the backing arena
+    pub fn into_inner(self) -> A { self.arena }
+}
+
+impl<A, K> Deref for WithKey<A, K> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target { &self.arena }
+}
+
+impl<A, K> DerefMut for WithKey<A, K> {
+    fn deref_mut(&mut self) -> &mut Self::Target { &mut self.arena }
+}
+
+impl<A: Index<K>, K> Index<K> for WithKey<A, K> {
+    type Output = A::Output;
+
+    #[track_caller]
+    fn index(&self, key: K) -> &Self::Output { &self.arena[key] }
+}
+
+impl<A: IndexMut<K>, K> IndexMut<K> for WithKey<A, K> {
+    #[track_caller]
+    fn index_mut(&mut self, key: K) -> &mut Self::Output { &mut self.arena[key] }
+}
+
+macro_rules! imp_with_key {
+    ($($arena:ident)*) => {$(
+        impl<T, I, V: Version> crate::base::$arena::Arena<T, I, V> {
+            /// Fix the key type of this arena to `K`, see [`WithKey`] for details
+            pub fn with_key<K: BuildArenaKey<I, V>>(self) -> WithKey<Self, K> { WithKey::new(self) }
+        }
+
+        impl<T, I, V: Version, K: BuildArenaKey<I, V>> WithKey<crate::base::$arena::Arena<T, I, V>, K> {
+            /// Insert a value in the arena, returning key assigned to the value.
+            ///
+            /// see [`Arena::insert`](crate::base::$arena::Arena::insert)
+            pub fn insert(&mut self, value: T) -> K { self.arena.insert(value) }
+
+            /// Insert a value in the arena, returning key assigned to the value
+            /// and a unique reference to the inserted value.
+            ///
+            /// see [`Arena::insert_mut`](crate::base::$arena::Arena::insert_mut)
+            pub fn insert_mut(&mut self, value: T) -> (K, &mut T) { self.arena.insert_mut(value) }
+
+            /// Return true if a value is associated with the given key.
+            pub fn contains(&self, key: K) -> bool { self.arena.contains(key) }
+
+            /// Remove and return the value associated with the given key.
+            ///
+            /// Panics if key is not associated with a value.
+            #[track_caller]
+            pub fn remove(&mut self, key: K) -> T { self.arena.remove(key) }
+
+            /// Remove and return the value associated with the given key.
+            ///
+            /// Returns `None` if key is not associated with a value.
+            pub fn try_remove(&mut self, key: K) -> Option<T> { self.arena.try_remove(key) }
+
+            /// Removes the value associated with the given key.
+            ///
+            /// Returns true if the value was removed, an false otherwise
+            pub fn delete(&mut self, key: K) -> bool { self.arena.delete(key) }
+
+            /// Return a shared reference to the value associated with the given key.
+            ///
+            /// If the given key is not associated with a value, then None is returned.
+            pub fn get(&self, key: K) -> Option<&T> { self.arena.get(key) }
+
+            /// Return a unique reference to the value associated with the given key.
+            ///
+            /// If the given key is not associated with a value, then None is returned.
+            pub fn get_mut(&mut self, key: K) -> Option<&mut T> { self.arena.get_mut(key) }
+        }
+    )*};
+}
+
+imp_with_key! { sparse hop dense }
+
+#[cfg(test)]
+mod test {
+    use crate::{base::dense::Arena, version::DefaultVersion};
+
+    #[test]
+    fn basic() {
+        let mut arena = Arena::<_, (), DefaultVersion>::new().with_key::<usize>();
+
+        let a = arena.insert(10);
+        let (b, value) = arena.insert_mut(20);
+        *value += 1;
+
+        assert!(arena.contains(a));
+        assert_eq!(arena.get(b), Some(&21));
+        assert_eq!(arena.remove(a), 10);
+        assert!(!arena.delete(a));
+        assert_eq!(arena.try_remove(b), Some(21));
+        assert!(arena.into_inner().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    cell::Cell,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use pui_arena::base::dense::Arena;
+
+struct PanicOnDrop<'a>(&'a Cell<usize>, bool);
+
+impl Drop for PanicOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+        if self.1 {
+            panic!("PanicOnDrop")
+        }
+    }
+}
+
+#[test]
+fn dense_drain_panic_safety() {
+    let drops = Cell::new(0);
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        let _: usize = arena.insert(PanicOnDrop(&drops, i == 3));
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| drop(arena.drain())));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 10);
+    assert!(arena.is_empty());
+
+    let key: usize = arena.insert(PanicOnDrop(&drops, false));
+    assert_eq!(arena.len(), 1);
+    assert!(arena.contains(key));
+}
+
+struct PanicOnClone<'a>(&'a Cell<usize>, bool);
+
+impl Clone for PanicOnClone<'_> {
+    fn clone(&self) -> Self {
+        if self.1 {
+            panic!("PanicOnClone")
+        }
+        Self(self.0, self.1)
+    }
+}
+
+impl Drop for PanicOnClone<'_> {
+    fn drop(&mut self) { self.0.set(self.0.get() + 1) }
+}
+
+#[test]
+fn dense_clone_from_panic_safety() {
+    let drops = Cell::new(0);
+    let mut source = Arena::new();
+    let mut arena = Arena::new();
+    for i in 0..5 {
+        let _: usize = source.insert(PanicOnClone(&drops, i == 3));
+        let _: usize = arena.insert(PanicOnClone(&drops, false));
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| arena.clone_from(&source)));
+    assert!(result.is_err());
+    assert!(arena.is_empty());
+
+    let key: usize = arena.insert(PanicOnClone(&drops, false));
+    assert_eq!(arena.len(), 1);
+    assert!(arena.contains(key));
+}
+
+#[test]
+fn dense_clone_after_interleaved_removes() {
+    use pui_arena::version::{TinyVersion, Version};
+    use std::rc::Rc;
+
+    type Key = pui_arena::Key<usize, <TinyVersion as Version>::Save>;
+
+    let value = Rc::new(());
+    let mut arena = Arena::<_, (), TinyVersion>::with_ident(());
+    let mut keys = Vec::new();
+
+    for i in 0..300 {
+        let key: Key = arena.insert(value.clone());
+        match i % 3 {
+            0 => keys.push(key),
+            1 => drop(arena.remove(key)),
+            _ => drop(arena.remove_stable(key)),
+        }
+    }
+
+    for &key in keys.iter().step_by(4) {
+        arena.remove(key);
+    }
+
+    let len = arena.len();
+    assert_eq!(Rc::strong_count(&value), len + 1);
+
+    let clone = arena.clone();
+    assert_eq!(clone.len(), len);
+    assert_eq!(clone.iter().count(), len);
+    assert_eq!(Rc::strong_count(&value), 2 * len + 1);
+
+    for &key in &keys {
+        assert_eq!(arena.contains(key), clone.contains(key));
+    }
+
+    drop(clone);
+    assert_eq!(Rc::strong_count(&value), len + 1);
+    drop(arena);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
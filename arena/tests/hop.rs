@@ -1,10 +1,10 @@
-use pui_arena::base::hop::Arena;
+use pui_arena::{base::hop::Arena, version::ExhaustionPolicy};
 
 type Key = pui_arena::Key<usize, SavedTestVersion>;
 
 #[derive(Debug, Clone, Copy)]
 struct TestVersion(u8);
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 struct SavedTestVersion(u8);
 
 unsafe impl pui_arena::version::Version for TestVersion {
@@ -48,3 +48,154 @@ fn hop_version_exhaustion() {
     let di = *a.id();
     assert_eq!(ci, di);
 }
+
+#[test]
+fn hop_remove_exhausted() {
+    use std::rc::Rc;
+
+    let value = Rc::new(());
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(value.clone());
+    arena.remove(a);
+
+    // `b` is in the last usable version of it's slot
+    let b: Key = arena.insert(value.clone());
+    let c: Key = arena.insert(value.clone());
+    assert_eq!(a.id(), b.id());
+
+    let removed = arena.remove(b);
+    assert!(Rc::ptr_eq(&removed, &value));
+    assert_eq!(Rc::strong_count(&value), 3);
+    drop(removed);
+
+    assert_eq!(arena.exhausted_slots(), 1);
+    assert!(arena.get(b).is_none());
+    assert_eq!(arena.keys::<usize>().collect::<Vec<_>>(), [*c.id()]);
+    assert_eq!(arena.iter().count(), 1);
+
+    // the exhausted slot is never reused
+    let d: Key = arena.insert(value.clone());
+    assert!(d.id() != b.id());
+    assert!(arena.delete(d));
+    assert_eq!(Rc::strong_count(&value), 2);
+
+    drop(arena);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn hop_exhausted_slots() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    arena.remove(a);
+    let a: Key = arena.insert(1);
+    assert_eq!(arena.exhausted_slots(), 0);
+    assert!(arena.delete(a));
+    assert_eq!(arena.exhausted_slots(), 1);
+
+    let b: Key = arena.insert(2);
+    assert_ne!(a.id(), b.id());
+    arena.remove(b);
+    let _: Key = arena.insert(3);
+    assert_eq!(arena.drain().count(), 1);
+    assert_eq!(arena.exhausted_slots(), 2);
+
+    arena.clear();
+    assert_eq!(arena.exhausted_slots(), 0);
+}
+
+#[test]
+#[should_panic(expected = "exhausted slots")]
+fn hop_on_exhaustion_panic() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    assert_eq!(arena.on_exhaustion(), ExhaustionPolicy::Retire);
+    let a: Key = arena.insert(0);
+    arena.remove(a);
+    let a: Key = arena.insert(1);
+    arena.remove(a);
+    let b: Key = arena.insert(2);
+    arena.remove(b);
+
+    arena.set_on_exhaustion(ExhaustionPolicy::Panic);
+    let _: Key = arena.insert(3);
+    let _: Key = arena.insert(4);
+}
+
+#[test]
+fn hop_remove_versioned() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    let (value, version) = arena.remove_versioned(a).unwrap();
+    assert_eq!((value, version.map(|version| version.0)), (0, Some(3)));
+    assert!(arena.remove_versioned(a).is_none());
+
+    let b: Key = arena.insert(1);
+    assert_eq!(b.id(), a.id());
+    assert!(Some(*b.version()) == version);
+    let (value, version) = arena.remove_versioned(b).unwrap();
+    assert_eq!((value, version.map(|version| version.0)), (1, None));
+}
+
+#[test]
+fn hop_retain_exhausted() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let keys: Vec<Key> = (0..6).map(|i| arena.insert(i)).collect();
+    let a = arena.remove(keys[2]);
+    let a: Key = arena.insert(a);
+    assert_eq!(a.id(), keys[2].id());
+
+    // the slot of `a` is exhausted in the middle of the removed run
+    arena.retain(|&mut i| i == 0 || i == 5);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.exhausted_slots(), 1);
+    assert_eq!(arena.free_slots().count(), 3);
+
+    let keys: Vec<Key> = (0..3).map(|i| arena.insert(i)).collect();
+    assert!(keys.iter().all(|key| key.id() != a.id()));
+    assert_eq!(arena.iter().count(), 5);
+}
+
+#[test]
+fn hop_retain_panic_safety() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::new();
+    for i in 0..10 {
+        let _: usize = arena.insert(i);
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.retain(|&mut i| {
+            assert_ne!(i, 6);
+            i < 2
+        })
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(arena.len(), 6);
+    assert!(arena.iter().copied().eq([0, 1, 6, 7, 8, 9]));
+    assert_eq!(arena.free_slots().count(), 4);
+    let key: usize = arena.insert(10);
+    assert!((3..=6).contains(&key));
+}
+
+#[test]
+fn hop_is_reused() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    let index = *a.id();
+    assert!(!arena.is_reused(index, *a.version()));
+    assert!(arena.is_reused(index + 1, *a.version()));
+
+    let (_, next) = arena.remove_versioned(a).unwrap();
+    assert!(arena.is_reused(index, *a.version()));
+    assert!(!arena.is_reused(index, next.unwrap()));
+
+    let b: Key = arena.insert(1);
+    assert!(Some(*b.version()) == next);
+    assert!(!arena.is_reused(index, *b.version()));
+
+    // exhaust the slot
+    arena.remove(b);
+    assert!(arena.is_reused(index, *b.version()));
+}
@@ -0,0 +1,206 @@
+#![cfg(feature = "slab")]
+
+macro_rules! slab_tests {
+    ($name:ident) => {
+        mod $name {
+            use pui_arena::slab::$name::Slab;
+
+            #[test]
+            fn insert_get_remove() {
+                let mut slab = Slab::new();
+                let a = slab.insert(10);
+                let b = slab.insert(20);
+                assert_eq!(slab[a], 10);
+                assert_eq!(slab[b], 20);
+                assert_eq!(slab.get(a), Some(&10));
+                assert_eq!(slab.len(), 2);
+
+                assert_eq!(slab.remove(a), 10);
+                assert_eq!(slab.get(a), None);
+                assert!(!slab.contains(a));
+                assert_eq!(slab.try_remove(a), None);
+                assert_eq!(slab.len(), 1);
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid_remove_panics() {
+                let mut slab = Slab::<u32>::new();
+                slab.remove(1);
+            }
+
+            #[test]
+            #[should_panic]
+            fn double_remove_panics() {
+                let mut slab = Slab::new();
+                let a = slab.insert(10);
+                slab.remove(a);
+                slab.remove(a);
+            }
+
+            #[test]
+            fn reuse_vacant() {
+                let mut slab = Slab::new();
+                let a = slab.insert(10);
+                slab.insert(20);
+                slab.remove(a);
+                assert_eq!(slab.insert(30), a);
+                assert_eq!(slab[a], 30);
+            }
+
+            #[test]
+            fn vacant_entry_key() {
+                let mut slab = Slab::new();
+                let first = slab.insert(0);
+                let entry = slab.vacant_entry();
+                let key = entry.key();
+                assert_eq!(entry.insert(key), key);
+                assert_eq!(slab[key], key);
+
+                slab.remove(first);
+                let entry = slab.vacant_entry();
+                let key = entry.key();
+                assert_eq!(entry.insert(key), key);
+                assert_eq!(slab[key], key);
+            }
+
+            #[test]
+            fn len_tracks_values() {
+                let mut slab = Slab::new();
+                for i in 0..10 {
+                    slab.insert(i);
+                }
+                assert_eq!(slab.len(), 10);
+
+                slab.retain(|&mut x| x % 2 == 0);
+                assert_eq!(slab.len(), 5);
+                assert_eq!(slab.iter().count(), 5);
+
+                slab.delete_all();
+                assert_eq!(slab.len(), 0);
+                assert!(slab.is_empty());
+
+                for i in 0..10 {
+                    slab.insert(i);
+                }
+                slab.clear();
+                assert_eq!(slab.len(), 0);
+                assert!(slab.is_empty());
+            }
+
+            #[test]
+            fn reserve_capacity() {
+                let mut slab = Slab::new();
+                slab.reserve(10);
+                let capacity = slab.capacity();
+                assert!(capacity >= 10);
+
+                for i in 0..10 {
+                    slab.insert(i);
+                }
+                assert_eq!(slab.capacity(), capacity);
+
+                slab.reserve(5);
+                assert!(slab.capacity() >= 15);
+            }
+
+            #[test]
+            fn into_iter_yields_keys() {
+                let mut slab = Slab::new();
+                let a = slab.insert(10);
+                let b = slab.insert(20);
+                let c = slab.insert(30);
+                slab.remove(b);
+
+                let mut items = (&slab).into_iter().map(|(key, &value)| (key, value)).collect::<Vec<_>>();
+                items.sort_unstable();
+                assert_eq!(items, [(a, 10), (c, 30)]);
+
+                for (_, value) in &mut slab {
+                    *value += 1;
+                }
+
+                let mut items = slab.into_iter().collect::<Vec<_>>();
+                items.sort_unstable();
+                assert_eq!(items, [(a, 11), (c, 31)]);
+            }
+
+            #[test]
+            fn get2_mut() {
+                let mut slab = Slab::new();
+                let key1 = slab.insert(1);
+                let key2 = slab.insert(2);
+                let key3 = slab.insert(3);
+
+                if let Some((x, y)) = slab.get2_mut(key1, key2) {
+                    *x += 1;
+                    *y += 2;
+                } else {
+                    panic!("both keys are in the slab")
+                }
+
+                assert_eq!(slab[key1], 2);
+                assert_eq!(slab[key2], 4);
+                assert_eq!(slab[key3], 3);
+
+                slab.remove(key3);
+                assert!(slab.get2_mut(key1, key3).is_none());
+                assert!(slab.get2_mut(key3, key1).is_none());
+                assert!(slab.get2_mut(key1, 100).is_none());
+            }
+
+            #[test]
+            #[should_panic]
+            fn get2_mut_same_key() {
+                let mut slab = Slab::new();
+                let key = slab.insert(1);
+                slab.get2_mut(key, key);
+            }
+
+            #[test]
+            fn key_of() {
+                let mut slab = Slab::new();
+                let keys = (0..10).map(|i| slab.insert(i)).collect::<Vec<_>>();
+                slab.remove(keys[3]);
+
+                for (key, value) in &slab {
+                    assert_eq!(slab.key_of(value), key);
+                }
+            }
+
+            #[test]
+            #[should_panic(expected = "The reference points to a value outside this slab")]
+            fn key_of_outside() {
+                let mut slab = Slab::new();
+                slab.insert(0);
+                slab.key_of(&0);
+            }
+        }
+    };
+}
+
+slab_tests!(sparse);
+slab_tests!(hop);
+slab_tests!(dense);
+
+#[test]
+fn sparse_reuse_order() {
+    let mut slab = pui_arena::slab::sparse::Slab::new();
+    let keys = (0..4).map(|i| slab.insert(i)).collect::<Vec<_>>();
+    slab.remove(keys[1]);
+    slab.remove(keys[3]);
+    assert_eq!(slab.insert(10), keys[3]);
+    assert_eq!(slab.insert(11), keys[1]);
+    assert_eq!(slab.insert(12), 4);
+}
+
+#[test]
+fn dense_reuse_order() {
+    let mut slab = pui_arena::slab::dense::Slab::new();
+    let keys = (0..4).map(|i| slab.insert(i)).collect::<Vec<_>>();
+    slab.remove(keys[1]);
+    slab.remove(keys[3]);
+    assert_eq!(slab.insert(10), keys[3]);
+    assert_eq!(slab.insert(11), keys[1]);
+    assert_eq!(slab.insert(12), 4);
+}
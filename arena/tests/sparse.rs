@@ -1,10 +1,10 @@
-use pui_arena::base::sparse::Arena;
+use pui_arena::{base::sparse::Arena, version::ExhaustionPolicy};
 
 type Key = pui_arena::Key<usize, SavedTestVersion>;
 
 #[derive(Debug, Clone, Copy)]
 struct TestVersion(u8);
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 struct SavedTestVersion(u8);
 
 unsafe impl pui_arena::version::Version for TestVersion {
@@ -48,3 +48,228 @@ fn sparse_version_exhaustion() {
     let di = *a.id();
     assert_eq!(ci, di);
 }
+
+#[test]
+fn sparse_remove_exhausted() {
+    use std::rc::Rc;
+
+    let value = Rc::new(());
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(value.clone());
+    arena.remove(a);
+
+    // `b` is in the last usable version of it's slot
+    let b: Key = arena.insert(value.clone());
+    let c: Key = arena.insert(value.clone());
+    assert_eq!(a.id(), b.id());
+
+    let removed = arena.remove(b);
+    assert!(Rc::ptr_eq(&removed, &value));
+    assert_eq!(Rc::strong_count(&value), 3);
+    drop(removed);
+
+    assert_eq!(arena.exhausted_slots(), 1);
+    assert!(arena.get(b).is_none());
+    assert_eq!(arena.keys::<usize>().collect::<Vec<_>>(), [*c.id()]);
+    assert_eq!(arena.iter().count(), 1);
+
+    // the exhausted slot is never reused
+    let d: Key = arena.insert(value.clone());
+    assert!(d.id() != b.id());
+    assert!(arena.delete(d));
+    assert_eq!(Rc::strong_count(&value), 2);
+
+    drop(arena);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn sparse_exhausted_slots() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    arena.remove(a);
+    let a: Key = arena.insert(1);
+    assert_eq!(arena.exhausted_slots(), 0);
+    assert!(arena.delete(a));
+    assert_eq!(arena.exhausted_slots(), 1);
+
+    let b: Key = arena.insert(2);
+    assert_ne!(a.id(), b.id());
+    arena.remove(b);
+    let _: Key = arena.insert(3);
+    assert_eq!(arena.drain().count(), 1);
+    assert_eq!(arena.exhausted_slots(), 2);
+
+    arena.clear();
+    assert_eq!(arena.exhausted_slots(), 0);
+}
+
+#[test]
+#[should_panic(expected = "exhausted slots")]
+fn sparse_on_exhaustion_panic() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    assert_eq!(arena.on_exhaustion(), ExhaustionPolicy::Retire);
+    let a: Key = arena.insert(0);
+    arena.remove(a);
+    let a: Key = arena.insert(1);
+    arena.remove(a);
+    let b: Key = arena.insert(2);
+    arena.remove(b);
+
+    arena.set_on_exhaustion(ExhaustionPolicy::Panic);
+    let _: Key = arena.insert(3);
+    let _: Key = arena.insert(4);
+}
+
+#[test]
+fn sparse_remove_versioned() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    let (value, version) = arena.remove_versioned(a).unwrap();
+    assert_eq!((value, version.map(|version| version.0)), (0, Some(3)));
+    assert!(arena.remove_versioned(a).is_none());
+
+    let b: Key = arena.insert(1);
+    assert_eq!(b.id(), a.id());
+    assert!(Some(*b.version()) == version);
+    let (value, version) = arena.remove_versioned(b).unwrap();
+    assert_eq!((value, version.map(|version| version.0)), (1, None));
+}
+
+#[test]
+#[cfg(feature = "pui")]
+fn id_across_collections() {
+    use pui_core::dynamic::Dynamic;
+    use pui_vec::{Id, PuiVec};
+
+    let mut arena = Arena::<_, _>::with_ident(Dynamic::create());
+    let mut names = PuiVec::new(Dynamic::create());
+
+    let a: Id<_> = arena.insert(10);
+    let b: Id<_> = arena.insert(20);
+    let x: Id<_> = names.push("a");
+
+    // foreign ids are bounds checked by the arena
+    assert_eq!(arena.get(x), Some(&10));
+    assert!(names.parse_id(a.get()) == Some(x));
+    assert!(names.parse_id(b.get()).is_none());
+
+    arena.remove(a);
+    assert_eq!(arena.get(x), None);
+    assert!(arena.parse_key::<Id<_>>(x.get()).is_none());
+}
+
+#[test]
+#[cfg(feature = "pui")]
+fn ids() {
+    use pui_core::dynamic::Dynamic;
+
+    let mut arena = Arena::<_, _>::with_ident(Dynamic::create());
+    let keys: Vec<usize> = (0..5).map(|i| arena.insert(i * 10)).collect();
+    arena.remove(keys[1]);
+    arena.remove(keys[3]);
+
+    let mut values = arena.ids().map(|id| arena[id]).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, [0, 20, 40]);
+}
+
+#[test]
+fn remove_by_ref() {
+    // not `Copy`, so it must be passed by reference to be reused
+    struct NodeId(pui_arena::Key<usize>);
+
+    pui_arena::impl_arena_key!(NodeId => .0: pui_arena::Key<usize>);
+
+    let mut arena = Arena::new();
+    let a: NodeId = arena.insert(10);
+
+    assert_eq!(arena.try_remove(&a), Some(10));
+    assert_eq!(arena.try_remove(&a), None);
+    assert!(!arena.contains(a));
+}
+
+#[test]
+fn sparse_clone_from_panic_safety() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    struct PanicOnClone(u8);
+
+    impl Clone for PanicOnClone {
+        fn clone(&self) -> Self {
+            if self.0 == 3 {
+                panic!("PanicOnClone")
+            }
+            Self(self.0)
+        }
+    }
+
+    let mut source = Arena::<_>::new();
+    let mut arena = Arena::<_>::new();
+    for i in 0..6 {
+        let _: usize = source.insert(PanicOnClone(i));
+    }
+    for i in 0..2 {
+        let _: usize = arena.insert(PanicOnClone(i + 10));
+    }
+    source.remove(1);
+
+    let result = catch_unwind(AssertUnwindSafe(|| arena.clone_from(&source)));
+    assert!(result.is_err());
+
+    // the arena is still consistent, even though it has only some of source's values
+    let len = arena.iter().count();
+    assert_eq!(arena.len(), len);
+    let key: usize = arena.insert(PanicOnClone(20));
+    assert_eq!(arena.len(), len + 1);
+    assert_eq!(arena.iter().count(), len + 1);
+    assert_eq!(arena[key].0, 20);
+}
+
+#[test]
+fn sparse_is_reused() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    let a: Key = arena.insert(0);
+    let index = *a.id();
+    assert!(!arena.is_reused(index, *a.version()));
+    assert!(arena.is_reused(index + 1, *a.version()));
+
+    let (_, next) = arena.remove_versioned(a).unwrap();
+    assert!(arena.is_reused(index, *a.version()));
+    assert!(!arena.is_reused(index, next.unwrap()));
+
+    let b: Key = arena.insert(1);
+    assert!(Some(*b.version()) == next);
+    assert!(!arena.is_reused(index, *b.version()));
+
+    // exhaust the slot
+    arena.remove(b);
+    assert!(arena.is_reused(index, *b.version()));
+}
+
+#[test]
+#[cfg(feature = "validate")]
+fn sparse_check_invariants() {
+    let mut arena = Arena::<_, (), TestVersion>::with_ident(());
+    arena.check_invariants();
+
+    let keys: Vec<Key> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.check_invariants();
+
+    for &key in keys.iter().step_by(2) {
+        arena.remove(key);
+    }
+    arena.check_invariants();
+
+    // exhaust a slot
+    let a: Key = arena.insert(10);
+    arena.remove(a);
+    assert_eq!(arena.exhausted_slots(), 1);
+    arena.check_invariants();
+
+    arena.retain(|&mut value| value % 3 != 0);
+    arena.check_invariants();
+
+    arena.clear();
+    arena.check_invariants();
+}
@@ -0,0 +1,87 @@
+use pui_core::Identifier;
+use typsy::{
+    hlist,
+    hlist::{Cons, Nil},
+    hlist_pat,
+};
+
+use seal::Seal;
+
+use crate::{IdCell, IdentifierExt};
+#[forbid(missing_docs)]
+mod seal {
+    pub trait Seal {}
+}
+
+/// A trait that defines how to get all of shared references
+/// out of the heterogeneous list of [`IdCell`]s.
+///
+/// Shared references may alias, so unlike [`GetAllMut`](crate::GetAllMut),
+/// the `IdCell`s don't need to be disjoint.
+///
+/// This is also implemented for tuples of up to 8 [`IdCell`]s
+///
+/// ```
+/// # use pui_cell::{IdCell, IdentifierExt};
+/// let ident = pui_core::dynamic::Dynamic::create();
+/// let a = ident.cell(0);
+/// let b = ident.cell("hello");
+/// let (a, b, c) = ident.get_all((&a, &b, &a));
+/// assert_eq!((*a, *b, *c), (0, "hello", 0));
+/// ```
+pub trait GetAll<I>: Seal {
+    /// A hlist or tuple of shared references
+    type Output;
+
+    /// Gets all shared references from the `IdCell`s
+    fn get_all(self, ident: I) -> Self::Output;
+}
+
+impl Seal for Nil {}
+
+impl<T> GetAll<T> for Nil {
+    type Output = Nil;
+
+    fn get_all(self, _: T) -> Self::Output { Self }
+}
+
+impl<T: ?Sized, R: Seal> Seal for Cons<&T, R> {}
+
+impl<'a, T: ?Sized, R, I: ?Sized + Identifier> GetAll<&'a I> for Cons<&'a IdCell<T, I::Token>, R>
+where
+    R: GetAll<&'a I>,
+{
+    type Output = Cons<&'a T, R::Output>;
+
+    fn get_all(self, ident: &'a I) -> Self::Output {
+        Cons {
+            value: ident.get(self.value),
+            rest: self.rest.get_all(ident),
+        }
+    }
+}
+
+macro_rules! tuple {
+    ($($T:ident $v:ident),*) => {
+        impl<$($T: ?Sized),*> Seal for ($(&$T,)*) {}
+
+        impl<'a, I: ?Sized + Identifier, $($T: ?Sized),*> GetAll<&'a I> for ($(&'a IdCell<$T, I::Token>,)*) {
+            type Output = ($(&'a $T,)*);
+
+            fn get_all(self, ident: &'a I) -> Self::Output {
+                let ($($v,)*) = self;
+                let hlist_pat!($($v),*) = hlist!($($v),*).get_all(ident);
+                ($($v,)*)
+            }
+        }
+    };
+}
+
+tuple!(A a);
+tuple!(A a, B b);
+tuple!(A a, B b, C c);
+tuple!(A a, B b, C c, D d);
+tuple!(A a, B b, C c, D d, E e);
+tuple!(A a, B b, C c, D d, E e, F f);
+tuple!(A a, B b, C c, D d, E e, F f, G g);
+tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
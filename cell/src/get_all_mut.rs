@@ -1,5 +1,9 @@
 use pui_core::Identifier;
-use typsy::hlist::{Cons, Nil};
+use typsy::{
+    hlist,
+    hlist::{Cons, Nil},
+    hlist_pat,
+};
 
 use seal::Seal;
 
@@ -14,8 +18,20 @@ mod seal {
 /// A trait that defines all the types defines how to get all
 /// of mutable references out of the heterogeneous list
 /// of [`IdCell`]s.
+///
+/// This is also implemented for tuples of up to 8 [`IdCell`]s
+///
+/// ```
+/// # use pui_cell::{IdCell, IdentifierExt};
+/// let mut ident = pui_core::dynamic::Dynamic::create();
+/// let a = ident.cell(0);
+/// let b = ident.cell("hello");
+/// let (a, b) = ident.get_all_mut((&a, &b));
+/// *a += 1;
+/// *b = "world";
+/// ```
 pub trait GetAllMut<I>: Seal {
-    /// A hlist of mutable references
+    /// A hlist or tuple of mutable references
     type Output;
 
     /// Gets all mutable references from the `IdCell`s, returns
@@ -62,3 +78,33 @@ where
         })
     }
 }
+
+macro_rules! tuple {
+    ($($T:ident $v:ident),*) => {
+        impl<$($T: ?Sized),*> Seal for ($(&$T,)*) {
+            fn __internal_find(&self, ptr: *mut ()) -> bool {
+                let ($($v,)*) = *self;
+                $($v as *const $T as *const () == ptr)||*
+            }
+        }
+
+        impl<'a, I: ?Sized + Identifier, $($T: ?Sized),*> GetAllMut<&'a mut I> for ($(&'a IdCell<$T, I::Token>,)*) {
+            type Output = ($(&'a mut $T,)*);
+
+            fn get_all_mut(self, ident: &'a mut I) -> Option<Self::Output> {
+                let ($($v,)*) = self;
+                let hlist_pat!($($v),*) = hlist!($($v),*).get_all_mut(ident)?;
+                Some(($($v,)*))
+            }
+        }
+    };
+}
+
+tuple!(A a);
+tuple!(A a, B b);
+tuple!(A a, B b, C c);
+tuple!(A a, B b, C c, D d);
+tuple!(A a, B b, C c, D d, E e);
+tuple!(A a, B b, C c, D d, E e, F f);
+tuple!(A a, B b, C c, D d, E e, F f, G g);
+tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
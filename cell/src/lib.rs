@@ -10,7 +10,9 @@
 
 use pui_core::Identifier;
 
+mod get_all;
 mod get_all_mut;
+pub use get_all::GetAll;
 pub use get_all_mut::GetAllMut;
 
 pub use typsy;
@@ -49,6 +51,80 @@ pub trait IdentifierExt: Identifier {
         unsafe { &mut *a.as_ptr() }
     }
 
+    /// Call the given closure with a unique reference from the [`IdCell`]
+    ///
+    /// The unique reference can't escape the closure, so the identifier is
+    /// free to hand out shared references again as soon as this returns.
+    /// This makes it easy to alternate between read phases, where many
+    /// shared references are live, and short write phases.
+    ///
+    /// ```rust
+    /// # use pui_cell::IdentifierExt;
+    /// pui_core::scope!(ident);
+    /// let mut ident = ident;
+    /// let a = ident.cell(1);
+    /// let b = ident.cell(2);
+    ///
+    /// // read phase
+    /// let sum = ident.get(&a) + ident.get(&b);
+    ///
+    /// // write phase
+    /// ident.with_mut(&a, |a| *a = sum);
+    ///
+    /// // read phase
+    /// assert_eq!(*ident.get(&a), 3);
+    /// assert_eq!(*ident.get(&b), 2);
+    /// ```
+    ///
+    /// It returns the closure's result, so it can also be used to update many cells
+    ///
+    /// ```rust
+    /// # use pui_cell::IdentifierExt;
+    /// pui_core::scope!(ident);
+    /// let mut ident = ident;
+    /// let cells = [ident.cell(1), ident.cell(2), ident.cell(3)];
+    ///
+    /// let old: Vec<i32> = cells.iter().map(|cell| ident.with_mut(cell, |x| std::mem::replace(x, *x * 10))).collect();
+    /// assert_eq!(old, [1, 2, 3]);
+    /// assert_eq!(*ident.get(&cells[2]), 30);
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Will panic if self doesn't own the `IdCell`
+    #[doc(alias = "update")]
+    fn with_mut<A: ?Sized, R, F: FnOnce(&mut A) -> R>(&mut self, a: &IdCell<A, Self::Token>, f: F) -> R {
+        f(self.get_mut(a))
+    }
+
+    /// Get a [`Debug`](core::fmt::Debug) printable view of the value in the [`IdCell`]
+    ///
+    /// ```rust
+    /// # use pui_cell::IdentifierExt;
+    /// pui_core::scope!(ident);
+    /// let cell = ident.cell(10);
+    /// assert_eq!(format!("{:?}", ident.debug_cell(&cell)), "IdCell { value: 10 }");
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// Will panic if self doesn't own the `IdCell`
+    fn debug_cell<'a, A: ?Sized + core::fmt::Debug>(&'a self, a: &'a IdCell<A, Self::Token>) -> DebugCell<'a, A> {
+        DebugCell { value: self.get(a) }
+    }
+
+    /// Get shared references from all of the [`IdCell`]s
+    ///
+    /// # Panic
+    ///
+    /// Will panic if self doesn't own any of the `IdCell`s
+    fn get_all<'a, L>(&'a self, list: L) -> L::Output
+    where
+        L: GetAll<&'a Self>,
+    {
+        list.get_all(self)
+    }
+
     /// Get unique references both of the [`IdCell`]s
     ///
     /// # Panic
@@ -112,21 +188,88 @@ pub trait IdentifierExt: Identifier {
             core::mem::swap(a, b)
         }
     }
+
+    /// Take the value out of the [`IdCell`], leaving `V::default()` in it's place
+    ///
+    /// # Panic
+    ///
+    /// Will panic if self doesn't own the `IdCell`
+    fn take_cell<V: Default>(&mut self, cell: &IdCell<V, Self::Token>) -> V { core::mem::take(self.get_mut(cell)) }
+
+    /// Swap the elements of two slice `IdCell`s
+    ///
+    /// # Panic
+    ///
+    /// Will panic if self doesn't own either of the `IdCell`s or
+    /// if the two slices have different lengths
+    fn swap_slices<V>(&mut self, a: &IdCell<[V], Self::Token>, b: &IdCell<[V], Self::Token>) {
+        let len = self.get(a).len();
+        assert_eq!(len, self.get(b).len(), "Tried to swap slices of different lengths");
+
+        let a = a.as_ptr() as *mut V;
+        let b = b.as_ptr() as *mut V;
+
+        // `ptr::swap` allows overlapping elements, so this is fine even if `a` and `b` alias
+        for i in 0..len {
+            unsafe { core::ptr::swap(a.add(i), b.add(i)) }
+        }
+    }
+}
+
+/// A [`Debug`](core::fmt::Debug) printable view of the value in an [`IdCell`],
+/// created by [`IdentifierExt::debug_cell`]
+pub struct DebugCell<'a, V: ?Sized> {
+    value: &'a V,
+}
+
+impl<V: ?Sized + core::fmt::Debug> core::fmt::Debug for DebugCell<'_, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IdCell").field("value", &self.value).finish()
+    }
 }
 
 struct Wrapper<T: ?Sized>(core::cell::UnsafeCell<T>);
-unsafe impl<T: Send + Sync> Sync for Wrapper<T> {}
 
 /// A thread-safe shared mutable type that can be
 /// allows references into it's interior (unlike `Cell`)
 /// without returning guards (unlike `RefCell`, `Mutex`,
 /// or `RwLock`).
+///
+/// An `IdCell` carries it's token, so it can only be sent to
+/// another thread if the token can be.
+///
+/// `IdCell` may hold unsized values, like slices and trait objects.
+/// These can be created by unsizing a sized `IdCell`, or with [`IdCell::from_mut`].
+///
+/// ```rust
+/// # use pui_cell::{IdCell, IdentifierExt};
+/// # use core::any::Any;
+/// pui_core::scope!(ident);
+/// let mut ident = ident;
+/// let cell: Box<IdCell<dyn Any, _>> = Box::new(ident.cell(0_i32));
+/// *ident.get_mut(&cell).downcast_mut::<i32>().unwrap() += 1;
+/// assert_eq!(ident.get(&cell).downcast_ref::<i32>(), Some(&1));
+/// ```
+///
+/// ```rust,compile_fail
+/// # use pui_cell::IdCell;
+/// struct NotSend(*mut ());
+///
+/// let cell = IdCell::with_token(0, NotSend(core::ptr::null_mut()));
+/// std::thread::spawn(move || drop(cell));
+/// ```
 pub struct IdCell<V: ?Sized, T> {
     /// The token that identifies this `IdCell`
     pub token: T,
     value: Wrapper<V>,
 }
 
+// `IdCell` owns both it's value and token, so it can be sent if both can be
+unsafe impl<V: ?Sized + Send, T: Send> Send for IdCell<V, T> {}
+// A shared `IdCell` can be used to get a unique reference to it's value
+// from any thread with the identifier, and gives out shared references to it's token
+unsafe impl<V: ?Sized + Send + Sync, T: Sync> Sync for IdCell<V, T> {}
+
 impl<V, T: pui_core::Trivial> IdCell<V, T> {
     /// Create a new `IdCell`
     pub fn new(value: V) -> Self { Self::with_token(value, T::INIT) }
@@ -173,9 +316,48 @@ impl<V: ?Sized, T: pui_core::Trivial> IdCell<V, T> {
     }
 }
 
+impl<V, T: pui_core::Trivial> IdCell<V, T> {
+    /// Reinterpret this cell as a cell of `U`
+    ///
+    /// ```rust
+    /// # use pui_cell::{IdCell, IdentifierExt};
+    /// pui_core::scoped::Scoped::with(|mut ident| {
+    ///     let cell = ident.cell(0_u32);
+    ///     let bytes: &IdCell<[u8; 4], _> = unsafe { cell.cast() };
+    ///     *ident.get_mut(bytes) = 7_u32.to_ne_bytes();
+    ///     assert_eq!(*ident.get(&cell), 7);
+    /// })
+    /// ```
+    ///
+    /// Note: this requires the token have the same layout as `()`
+    /// and be [`Trivial`](pui_core::Trivial). The [`Trivial`](pui_core::Trivial)
+    /// requirement is handled by traits, but if you try and call this with
+    /// a token that has a different layout from `()`, `cast` this will panic.
+    ///
+    /// # Panics
+    ///
+    /// If `U` and `V` have different sizes, or if this cell isn't aligned for `U`
+    ///
+    /// # Safety
+    ///
+    /// Both cells can be written to, so every value of `V` must be a valid value of `U`,
+    /// and every value of `U` must be a valid value of `V`. For example, integers and arrays
+    /// of integers, but not `bool`, `char`, references, or types with padding.
+    pub unsafe fn cast<U>(&self) -> &IdCell<U, T> {
+        Self::assert_trivial();
+        assert_eq!(core::mem::size_of::<V>(), core::mem::size_of::<U>());
+        let ptr = self.as_ptr();
+        assert_eq!(ptr as usize % core::mem::align_of::<U>(), 0, "the cell isn't aligned for the new type");
+        &*(ptr as *const IdCell<U, T>)
+    }
+}
+
 impl<V, T: pui_core::Trivial> IdCell<[V], T> {
     /// Convert a cell of a slice to a slice of cells
     ///
+    /// This is specific to slices, other unsized values like trait objects
+    /// can't be split into multiple cells.
+    ///
     /// Note: this requires the token have the same layout as `()`
     /// and be [`Trivial`](pui_core::Trivial). The [`Trivial`](pui_core::Trivial)
     /// requirement is handled by traits, but if you try and call this with
@@ -202,3 +384,45 @@ impl<V, T: pui_core::Trivial> IdCell<[V], T> {
         unsafe { &mut *ptr }
     }
 }
+
+impl<V, T: pui_core::Trivial, const N: usize> IdCell<[V; N], T> {
+    /// Convert a cell of an array to an array of cells
+    ///
+    /// Like [`as_slice_of_cells`](IdCell::as_slice_of_cells), but the length is kept in the type
+    ///
+    /// ```rust
+    /// # use pui_cell::{IdCell, IdentifierExt};
+    /// pui_core::scoped::Scoped::with(|mut ident| {
+    ///     let cell = ident.cell([0_u8; 4]);
+    ///     let cells: &[IdCell<u8, _>; 4] = cell.as_array_of_cells();
+    ///     *ident.get_mut(&cells[1]) = 10;
+    ///     assert_eq!(*ident.get(&cell), [0, 10, 0, 0]);
+    /// })
+    /// ```
+    ///
+    /// Note: this requires the token have the same layout as `()`
+    /// and be [`Trivial`](pui_core::Trivial). The [`Trivial`](pui_core::Trivial)
+    /// requirement is handled by traits, but if you try and call this with
+    /// a token that has a different layout from `()`, `as_array_of_cells`
+    /// this will panic.
+    pub fn as_array_of_cells(&self) -> &[IdCell<V, T>; N] {
+        Self::assert_trivial();
+        let ptr = self.as_ptr();
+        let ptr = ptr as *const [IdCell<V, T>; N];
+        unsafe { &*ptr }
+    }
+
+    /// Convert a cell of an array to an array of cells
+    ///
+    /// Note: this requires the token have the same layout as `()`
+    /// and be [`Trivial`](pui_core::Trivial). The [`Trivial`](pui_core::Trivial)
+    /// requirement is handled by traits, but if you try and call this with
+    /// a token that has a different layout from `()`, `as_array_of_cells_mut`
+    /// this will panic.
+    pub fn as_array_of_cells_mut(&mut self) -> &mut [IdCell<V, T>; N] {
+        Self::assert_trivial();
+        let ptr = self.as_ptr();
+        let ptr = ptr as *mut [IdCell<V, T>; N];
+        unsafe { &mut *ptr }
+    }
+}
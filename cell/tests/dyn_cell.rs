@@ -0,0 +1,42 @@
+#![forbid(unsafe_code)]
+
+use std::any::Any;
+
+use pui_cell::{IdCell, IdentifierExt};
+use pui_core::scoped::{Scoped, ScopedToken};
+
+#[test]
+fn unsize() {
+    Scoped::with(|mut ident| {
+        let cells: Vec<Box<IdCell<dyn Any, ScopedToken<'_>>>> =
+            vec![Box::new(ident.cell(0_i32)), Box::new(ident.cell("hello"))];
+
+        *ident.get_mut(&cells[0]).downcast_mut::<i32>().unwrap() += 10;
+        let (a, b) = ident.get_mut2(&cells[0], &cells[1]);
+        *b.downcast_mut::<&str>().unwrap() = "world";
+        assert_eq!(a.downcast_ref::<i32>(), Some(&10));
+
+        assert_eq!(ident.get(&cells[1]).downcast_ref::<&str>(), Some(&"world"));
+        assert!(ident.get(&cells[1]).downcast_ref::<i32>().is_none());
+    })
+}
+
+#[test]
+fn from_mut() {
+    Scoped::with(|mut ident| {
+        let mut value = 0_u8;
+        let value: &mut dyn Any = &mut value;
+        let cell = IdCell::<dyn Any, ScopedToken<'_>>::from_mut(value);
+        let cell = &*cell;
+
+        *ident.get_mut(cell).downcast_mut::<u8>().unwrap() = 1;
+        assert_eq!(ident.get(cell).downcast_ref::<u8>(), Some(&1));
+    })
+}
+
+#[test]
+fn get_mut() {
+    let mut cell: Box<IdCell<dyn Any, ScopedToken<'static>>> = Box::new(IdCell::new(String::from("hello")));
+    cell.get_mut().downcast_mut::<String>().unwrap().push_str(" world");
+    assert_eq!(cell.get_mut().downcast_ref::<String>().map(String::as_str), Some("hello world"));
+}
@@ -0,0 +1,29 @@
+#![forbid(unsafe_code)]
+
+use std::{sync::Arc, thread};
+
+use pui_cell::IdentifierExt;
+use pui_core::dynamic::Dynamic;
+
+#[test]
+fn shared_ident() {
+    let mut ident = Dynamic::create();
+    let cell = Arc::new(ident.cell(0_u32));
+    *ident.get_mut(&cell) = 10;
+
+    let ident = Arc::new(ident);
+    let readers: Vec<_> = (0..4)
+        .map(|i| {
+            let ident = ident.clone();
+            let cell = cell.clone();
+            thread::spawn(move || *ident.get(&cell) + i)
+        })
+        .collect();
+
+    let sum: u32 = readers.into_iter().map(|reader| reader.join().unwrap()).sum();
+    assert_eq!(sum, 46);
+
+    let mut ident = Arc::try_unwrap(ident).ok().unwrap();
+    *ident.get_mut(&cell) += 1;
+    assert_eq!(*ident.get(&cell), 11);
+}
@@ -0,0 +1,308 @@
+//! Derive macros for [`pui-core`](https://crates.io/crates/pui-core)
+//!
+//! These are re-exported from `pui-core` with the `derive` feature,
+//! and the generated code refers to `::pui_core`.
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Implement `pui_core::Init` for a struct, every field is initialized with it's own `Init::INIT`
+///
+/// ```rust
+/// # use pui_core::Init;
+/// #[derive(Init)]
+/// struct MyToken;
+///
+/// #[derive(Init)]
+/// struct Pair<T> {
+///     token: MyToken,
+///     value: Option<T>,
+/// }
+///
+/// const PAIR: Pair<u8> = Pair::INIT;
+/// assert!(PAIR.value.is_none());
+/// ```
+#[proc_macro_derive(Init)]
+pub fn derive_init(input: TokenStream) -> TokenStream {
+    match Struct::parse(input, "Init") {
+        Ok(item) => item.impl_init(),
+        Err(error) => error,
+    }
+}
+
+/// Implement `pui_core::Trivial` for a struct that is already `Init` and `Token`
+///
+/// ```rust
+/// # use pui_core::{Init, Trivial, Token};
+/// #[derive(Init, Trivial, Clone, PartialEq, Eq, Hash)]
+/// struct MyToken;
+///
+/// unsafe impl Token for MyToken {}
+/// ```
+#[proc_macro_derive(Trivial)]
+pub fn derive_trivial(input: TokenStream) -> TokenStream {
+    match Struct::parse(input, "Trivial") {
+        Ok(item) => item.impl_trivial(),
+        Err(error) => error,
+    }
+}
+
+enum Fields {
+    Unit,
+    Tuple(Vec<String>),
+    Named(Vec<(String, String)>),
+}
+
+struct Struct {
+    name: String,
+    impl_generics: Vec<String>,
+    ty_generics: Vec<String>,
+    where_clause: Vec<String>,
+    fields: Fields,
+}
+
+fn error(message: &str) -> TokenStream {
+    format!("compile_error!({:?});", message)
+        .parse()
+        .expect("a `compile_error!` invocation is valid")
+}
+
+fn is_punct(tt: Option<&TokenTree>, ch: char) -> bool { matches!(tt, Some(TokenTree::Punct(p)) if p.as_char() == ch) }
+
+fn is_ident(tt: Option<&TokenTree>, name: &str) -> bool {
+    matches!(tt, Some(TokenTree::Ident(ident)) if ident.to_string() == name)
+}
+
+fn to_string(tokens: &[TokenTree]) -> String { tokens.iter().cloned().collect::<TokenStream>().to_string() }
+
+/// Split `tokens` on commas that aren't nested in angle brackets
+fn split_commas(tokens: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut segments = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+
+    for (i, tt) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(punct) = tt {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // the `>` in `->` doesn't close an angle bracket
+                '>' if i > 0 && is_punct(tokens.get(i - 1), '-') => (),
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    segments.push(&tokens[start..i]);
+                    start = i + 1;
+                }
+                _ => (),
+            }
+        }
+    }
+
+    if start < tokens.len() {
+        segments.push(&tokens[start..]);
+    }
+
+    segments
+}
+
+/// Remove a default from a generic parameter, `T: Bound = Default` becomes `T: Bound`
+fn strip_default(tokens: &[TokenTree]) -> &[TokenTree] {
+    let mut depth = 0_usize;
+
+    for (i, tt) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(punct) = tt {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if i > 0 && is_punct(tokens.get(i - 1), '-') => (),
+                '>' => depth = depth.saturating_sub(1),
+                '=' if depth == 0 && punct.spacing() == Spacing::Alone => return &tokens[..i],
+                _ => (),
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Skip attributes and visibility at the start of `tokens`
+fn skip_attrs_and_vis(tokens: &[TokenTree]) -> &[TokenTree] {
+    let mut tokens = tokens;
+
+    while is_punct(tokens.first(), '#') {
+        tokens = &tokens[2..];
+    }
+
+    if is_ident(tokens.first(), "pub") {
+        tokens = &tokens[1..];
+        if let Some(TokenTree::Group(group)) = tokens.first() {
+            if group.delimiter() == Delimiter::Parenthesis {
+                tokens = &tokens[1..];
+            }
+        }
+    }
+
+    tokens
+}
+
+impl Struct {
+    fn parse(input: TokenStream, derive: &str) -> Result<Self, TokenStream> {
+        let tokens = input.into_iter().collect::<Vec<_>>();
+        let mut tokens = skip_attrs_and_vis(&tokens);
+
+        if !is_ident(tokens.first(), "struct") {
+            return Err(error(&format!("`{}` can only be derived for structs", derive)))
+        }
+
+        let name = match tokens.get(1) {
+            Some(TokenTree::Ident(name)) => name.to_string(),
+            _ => return Err(error("expected the name of the struct")),
+        };
+        tokens = &tokens[2..];
+
+        let mut impl_generics = Vec::new();
+        let mut ty_generics = Vec::new();
+
+        if is_punct(tokens.first(), '<') {
+            let mut depth = 0_usize;
+            let end = tokens
+                .iter()
+                .enumerate()
+                .position(|(i, tt)| match tt {
+                    TokenTree::Punct(p) if p.as_char() == '<' => {
+                        depth += 1;
+                        false
+                    }
+                    TokenTree::Punct(p) if p.as_char() == '>' && !(i > 0 && is_punct(tokens.get(i - 1), '-')) => {
+                        depth -= 1;
+                        depth == 0
+                    }
+                    _ => false,
+                })
+                .ok_or_else(|| error("unclosed generic parameters"))?;
+
+            for param in split_commas(&tokens[1..end]) {
+                let param = skip_attrs_and_vis(param);
+                impl_generics.push(to_string(strip_default(param)));
+                ty_generics.push(if is_punct(param.first(), '\'') {
+                    to_string(&param[..2])
+                } else if is_ident(param.first(), "const") {
+                    to_string(&param[1..2])
+                } else {
+                    to_string(&param[..1])
+                });
+            }
+
+            tokens = &tokens[end + 1..];
+        }
+
+        let mut fields = Fields::Unit;
+        let mut where_clause = Vec::new();
+
+        if let Some(TokenTree::Group(group)) = tokens.first() {
+            if group.delimiter() == Delimiter::Parenthesis {
+                let body = group.stream().into_iter().collect::<Vec<_>>();
+                fields = Fields::Tuple(
+                    split_commas(&body)
+                        .into_iter()
+                        .map(|field| to_string(skip_attrs_and_vis(field)))
+                        .collect(),
+                );
+                tokens = &tokens[1..];
+            }
+        }
+
+        if is_ident(tokens.first(), "where") {
+            let end = tokens
+                .iter()
+                .position(|tt| match tt {
+                    TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+                    TokenTree::Punct(punct) => punct.as_char() == ';',
+                    _ => false,
+                })
+                .unwrap_or(tokens.len());
+
+            where_clause = split_commas(&tokens[1..end]).into_iter().map(to_string).collect();
+            tokens = &tokens[end..];
+        }
+
+        if let Some(TokenTree::Group(group)) = tokens.first() {
+            if group.delimiter() == Delimiter::Brace {
+                let body = group.stream().into_iter().collect::<Vec<_>>();
+                let mut named = Vec::new();
+
+                for field in split_commas(&body) {
+                    let field = skip_attrs_and_vis(field);
+                    match field.first() {
+                        Some(TokenTree::Ident(name)) if is_punct(field.get(1), ':') => {
+                            named.push((name.to_string(), to_string(&field[2..])))
+                        }
+                        _ => return Err(error("expected a named field")),
+                    }
+                }
+
+                fields = Fields::Named(named);
+            }
+        }
+
+        Ok(Self {
+            name,
+            impl_generics,
+            ty_generics,
+            where_clause,
+            fields,
+        })
+    }
+
+    fn header(&self, trait_: &str, bounds: &[String]) -> String {
+        let where_clause = self.where_clause.iter().chain(bounds).cloned().collect::<Vec<_>>();
+
+        format!(
+            "impl<{}> ::pui_core::{} for {}<{}> where {}",
+            self.impl_generics.join(", "),
+            trait_,
+            self.name,
+            self.ty_generics.join(", "),
+            where_clause.join(", "),
+        )
+    }
+
+    fn impl_init(&self) -> TokenStream {
+        let init = |ty: &String| format!("<{} as ::pui_core::Init>::INIT", ty);
+        let bound = |ty: &String| format!("{}: ::pui_core::Init", ty);
+
+        let (value, bounds) = match &self.fields {
+            Fields::Unit => ("Self".to_string(), Vec::new()),
+            Fields::Tuple(fields) => (
+                format!("Self({})", fields.iter().map(init).collect::<Vec<_>>().join(", ")),
+                fields.iter().map(bound).collect(),
+            ),
+            Fields::Named(fields) => (
+                format!(
+                    "Self {{ {} }}",
+                    fields
+                        .iter()
+                        .map(|(name, ty)| format!("{}: {}", name, init(ty)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                fields.iter().map(|(_, ty)| bound(ty)).collect(),
+            ),
+        };
+
+        format!("{} {{ const INIT: Self = {}; }}", self.header("Init", &bounds), value)
+            .parse()
+            .expect("the generated `Init` impl is valid")
+    }
+
+    fn impl_trivial(&self) -> TokenStream {
+        let bound = format!(
+            "{}<{}>: ::pui_core::Init + ::pui_core::Token",
+            self.name,
+            self.ty_generics.join(", ")
+        );
+
+        format!("{} {{}}", self.header("Trivial", &[bound]))
+            .parse()
+            .expect("the generated `Trivial` impl is valid")
+    }
+}
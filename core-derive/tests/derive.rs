@@ -0,0 +1,62 @@
+use core::{cell::Cell, marker::PhantomData};
+
+use pui_core::{Init, Token, Trivial};
+
+#[derive(Init, Trivial, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Unit;
+
+unsafe impl Token for Unit {}
+
+#[derive(Init, Trivial, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Branded<'a>(PhantomData<fn(&'a ()) -> &'a ()>);
+
+unsafe impl Token for Branded<'_> {}
+
+#[derive(Init)]
+struct Tuple<T>(Unit, Option<T>);
+
+type Callback = fn(u8) -> u8;
+
+#[derive(Init)]
+struct Named<'a, T: Copy = (), const N: usize = 2>
+where
+    T: Init,
+{
+    #[allow(dead_code)]
+    pub unit: Unit,
+    pub(crate) value: T,
+    cells: Option<&'a [T; N]>,
+    count: Cell<Option<Callback>>,
+}
+
+#[derive(Init)]
+struct TupleWhere<T>(Option<Vec<T>>, ())
+where
+    T: Clone;
+
+fn assert_trivial<T: Trivial>() {}
+
+#[test]
+fn unit() {
+    assert_trivial::<Unit>();
+    assert_trivial::<Branded<'static>>();
+    assert_eq!(Unit::INIT, Unit);
+}
+
+#[test]
+fn tuple() {
+    const TUPLE: Tuple<String> = Tuple::INIT;
+    assert_eq!(TUPLE.0, Unit);
+    assert!(TUPLE.1.is_none());
+
+    let TupleWhere(values, ()) = TupleWhere::<u8>::INIT;
+    assert!(values.is_none());
+}
+
+#[test]
+fn named() {
+    let named: Named = Named::INIT;
+    assert_eq!(named.value, ());
+    assert!(named.cells.is_none());
+    assert!(named.count.get().is_none());
+}
@@ -143,6 +143,7 @@ unsafe impl<A: ScalarAllocator> OneShotIdentifier for Dynamic<A> {}
 unsafe impl<A: ScalarAllocator, P: PoolMut<A>> Identifier for Dynamic<A, P> {
     type Token = DynamicToken<A>;
 
+    /// Compares the scalars directly, without creating a new token
     #[inline]
     fn owns_token(&self, token: &Self::Token) -> bool { self.owns_token(token) }
 
@@ -85,11 +85,21 @@ mod seal {
 }
 
 /// A const initializer
+///
+/// With the `derive` feature, this can be derived for structs whose fields are all `Init`
 pub trait Init {
     /// The initial value of `Self`
     const INIT: Self;
 }
 
+impl<T: ?Sized> Init for core::marker::PhantomData<T> {
+    const INIT: Self = core::marker::PhantomData;
+}
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use pui_core_derive::{Init, Trivial};
+
 /// A type that an [`Identifier`] produces and is owned by an `Identifier`
 ///
 /// If two tokens compare equal, then they should behave identically under
@@ -103,6 +113,12 @@ pub trait Init {
 pub unsafe trait Token: Clone + Eq {}
 /// A [`Token`] that has no safety requirements
 pub trait Trivial: Token + Init {}
+/// A [`Token`] that can be hashed, so it can be used as a key in
+/// hash maps and sets, for example to dedup identifiers
+///
+/// This is implemented for all tokens that implement [`Hash`](core::hash::Hash)
+pub trait HashableToken: Token + core::hash::Hash {}
+impl<T: Token + core::hash::Hash> HashableToken for T {}
 
 /// An [`Identifier`] who's tokens are guaranteed to *never* be owned by another
 /// `Identifier`, even if this one is dropped
@@ -126,12 +142,22 @@ pub unsafe trait OneShotIdentifier: Identifier {}
 /// * Two instances of `Identifier` must *never* return true for the same
 ///     token if either the two identifier or the tokens they generate can
 ///     both exist on the same thread.
+///
+/// `Identifier` is forwarded through `&mut I` and `Box<I>`, but not through shared
+/// pointers like `Rc<I>` or `Arc<I>`. Clones of those would own the same tokens,
+/// so each clone could hand out a unique reference to the same value. To share
+/// an identifier between readers, share an `Arc<I>` and use the `&I` it derefs to.
 pub unsafe trait Identifier {
     /// The tokens that this `Identifier` generates
     type Token: Token;
 
     #[inline]
     /// Check if this token was created by this identifier
+    ///
+    /// The default implementation creates a new token to compare against,
+    /// so identifiers that can check their tokens more cheaply should override this.
+    /// [`Dynamic`](dynamic::Dynamic) compares it's scalar directly, and
+    /// [`Scoped`](scoped::Scoped) owns every token of it's type.
     fn owns_token(&self, token: &Self::Token) -> bool { self.token() == *token }
 
     /// Create a new token
@@ -263,6 +263,45 @@ macro_rules! __scalar_allocator {
 /// }
 ///```
 ///
+/// All scalars are allocated in increasing order, and once the backing type's
+/// maximum is reached the allocator is exhausted, and `alloc` panics instead of
+/// wrapping around and reusing old scalars. If exhaustion is a concern for a long-running
+/// process, use `u128`, which can't be exhausted in practice.
+///
+/// ```
+/// pui_core::scalar_allocator! {
+///     pub struct Huge(u128);
+/// }
+///```
+///
+/// # Singletons
+///
+/// A unit scalar allocator like `Foo` can only produce one scalar, so `Foo::oneshot()`
+/// succeeds exactly once per type, checked by a `static` flag. The resulting
+/// `Dynamic<Foo>` is zero-sized and implements [`OneShotIdentifier`](crate::OneShotIdentifier),
+/// so it can be used for a single global instance, for example one stored in a `OnceCell`.
+///
+/// There is no `const` constructor, because each use of a `const` is a new value,
+/// which would allow creating more than one instance.
+///
+/// ```rust
+/// use pui_core::{dynamic::Dynamic, OneShotIdentifier};
+///
+/// pui_core::scalar_allocator! {
+///     struct Registry;
+/// }
+///
+/// fn assert_oneshot<I: OneShotIdentifier>(_: &I) {}
+///
+/// let registry = Registry::oneshot();
+/// assert_oneshot(&registry);
+/// assert_eq!(core::mem::size_of::<Dynamic<Registry>>(), 0);
+///
+/// // the second call panics, even after the first `Dynamic<Registry>` is dropped
+/// drop(registry);
+/// assert!(std::panic::catch_unwind(Registry::oneshot).is_err());
+/// ```
+///
 /// You can also prefix `struct` with `thread_local` to get a [`ScalarAllocator`]
 /// that is only produces unique scalars on within a given thread
 ///
@@ -402,3 +441,85 @@ norm_prim! {
     u64 => AtomicU64, num::NonZeroU64,
     usize => AtomicUsize, num::NonZeroUsize,
 }
+
+/// A `u128` counter that can be stored in a `static`, there is no `AtomicU128`
+/// on all platforms, so this uses a spin lock instead
+#[doc(hidden)]
+pub struct AtomicCounter128 {
+    lock: AtomicBool,
+    value: core::cell::UnsafeCell<u128>,
+}
+
+// SAFETY: `value` is only accessed while `lock` is held
+unsafe impl Sync for AtomicCounter128 {}
+
+impl AtomicCounter128 {
+    const fn new(value: u128) -> Self {
+        Self {
+            lock: AtomicBool::new(false),
+            value: core::cell::UnsafeCell::new(value),
+        }
+    }
+
+    fn inc(&self) -> Option<u128> {
+        while self.lock.compare_exchange_weak(false, true, Acquire, Relaxed).is_err() {
+            core::hint::spin_loop()
+        }
+
+        // SAFETY: the lock is held, so there are no other accesses to `value`
+        let value = unsafe { &mut *self.value.get() };
+        let current = *value;
+        let next = current.checked_add(1);
+        if let Some(next) = next {
+            *value = next;
+        }
+
+        self.lock.store(false, Release);
+
+        next.map(|_| current)
+    }
+}
+
+fn inc_local_128(local: &Cell<u128>) -> Option<u128> {
+    let value = local.get();
+    local.set(value.checked_add(1)?);
+    Some(value)
+}
+
+impl crate::Seal for u128 {}
+impl Scalar for u128 {
+    #[doc(hidden)]
+    type Local = Cell<u128>;
+    #[doc(hidden)]
+    type Atomic = AtomicCounter128;
+
+    #[doc(hidden)]
+    const LOCAL_INIT: Self::Local = Cell::new(0);
+    #[doc(hidden)]
+    const ATOMIC_INIT: Self::Atomic = AtomicCounter128::new(0);
+
+    #[inline]
+    #[doc(hidden)]
+    fn inc_local(local: &Self::Local) -> Option<Self> { inc_local_128(local) }
+    #[doc(hidden)]
+    fn inc_atomic(local: &Self::Atomic) -> Option<Self> { local.inc() }
+}
+
+impl crate::Seal for num::NonZeroU128 {}
+impl Scalar for num::NonZeroU128 {
+    #[doc(hidden)]
+    type Local = Cell<u128>;
+    #[doc(hidden)]
+    type Atomic = AtomicCounter128;
+
+    #[doc(hidden)]
+    const LOCAL_INIT: Self::Local = Cell::new(0);
+    #[doc(hidden)]
+    const ATOMIC_INIT: Self::Atomic = AtomicCounter128::new(0);
+
+    #[inline]
+    #[doc(hidden)]
+    fn inc_local(local: &Self::Local) -> Option<Self> { Self::new(inc_local_128(local)?.wrapping_add(1)) }
+    #[doc(hidden)]
+    fn inc_atomic(local: &Self::Atomic) -> Option<Self> { Self::new(local.inc()?.wrapping_add(1)) }
+}
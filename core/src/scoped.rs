@@ -9,7 +9,7 @@
 //! This allows [`Scoped::with`] to prevent the following at
 //! compile time:
 //! ```rust,compile_fail
-//! # use pui_core::Scoped;
+//! # use pui_core::scoped::Scoped;
 //! Scoped::with(|a: Scoped<'_>| {
 //!     Scoped::with(|b: Scoped<'_>| {
 //!         assert_eq!(a, b);
@@ -123,6 +123,30 @@ impl<'scope> Scoped<'scope> {
     #[inline]
     pub fn with<R, F: FnOnce(Scoped<'_>) -> R>(f: F) -> R { f(unsafe { Self::new_unchecked() }) }
 
+    /// Call the given closure with a new `Scoped` nested inside this one
+    ///
+    /// The nested `Scoped` has a fresh brand, so it can't be confused with this one
+    ///
+    /// ```rust
+    /// # use pui_core::scoped::Scoped;
+    /// Scoped::with(|outer| {
+    ///     outer.scope_within(|inner| {
+    ///         assert_eq!(inner.token(), inner.token());
+    ///     })
+    /// })
+    /// ```
+    ///
+    /// ```rust,compile_fail
+    /// # use pui_core::scoped::Scoped;
+    /// Scoped::with(|outer| {
+    ///     outer.scope_within(|inner| {
+    ///         assert_eq!(outer.token(), inner.token());
+    ///     })
+    /// })
+    /// ```
+    #[inline]
+    pub fn scope_within<R, F: FnOnce(Scoped<'_>) -> R>(&self, f: F) -> R { Scoped::with(f) }
+
     /// Create a new scoped token
     pub const fn token(&self) -> ScopedToken<'scope> { ScopedToken::new() }
 }
@@ -143,6 +167,7 @@ unsafe impl crate::OneShotIdentifier for Scoped<'_> {}
 unsafe impl<'a> Identifier for Scoped<'a> {
     type Token = ScopedToken<'a>;
 
+    /// The brand guarantees that every `ScopedToken<'a>` was created by this `Scoped<'a>`
     #[inline]
     fn owns_token(&self, _: &Self::Token) -> bool { true }
 
@@ -19,3 +19,58 @@ fn global() {
     }
     std::panic::catch_unwind(Global::oneshot).err().unwrap();
 }
+
+#[test]
+fn token_set() {
+    use pui_core::{dynamic::Dynamic, HashableToken};
+    use std::collections::HashSet;
+
+    fn registry<T: HashableToken>(tokens: Vec<T>) -> HashSet<T> { tokens.into_iter().collect() }
+
+    let a = Dynamic::create();
+    let b = Dynamic::create();
+    let tokens = registry(vec![a.token(), b.token(), a.token()]);
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens.contains(&b.token()));
+}
+
+pui_core::scalar_allocator! {
+    thread_local struct LocalTiny(u8);
+}
+
+pui_core::scalar_allocator! {
+    struct Huge(u128);
+}
+
+pui_core::scalar_allocator! {
+    thread_local struct LocalHuge(core::num::NonZeroU128);
+}
+
+#[test]
+fn exhaust_without_wraparound() {
+    use pui_core::scalar::ScalarAllocator;
+
+    let scalars = (0..255).map(|_| LocalTiny::alloc()).collect::<Vec<_>>();
+    assert_eq!(scalars, (0..255).collect::<Vec<u8>>());
+
+    // the allocator stays exhausted instead of wrapping around to `0`
+    for _ in 0..3 {
+        std::panic::catch_unwind(LocalTiny::alloc).err().unwrap();
+    }
+}
+
+#[test]
+fn u128_scalars() {
+    use pui_core::scalar::ScalarAllocator;
+
+    let a = Huge::alloc();
+    let b = Huge::alloc();
+    assert!(a < b);
+
+    assert_eq!(LocalHuge::alloc().get(), 1);
+    assert_eq!(LocalHuge::alloc().get(), 2);
+
+    let a = Huge::oneshot();
+    let b = Huge::oneshot();
+    assert!(a.token() != b.token());
+}
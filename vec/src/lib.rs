@@ -15,14 +15,21 @@
 extern crate alloc as std;
 
 use core::ops::{Deref, DerefMut, Index, IndexMut};
-use std::vec::Vec;
+use std::{collections::TryReserveError, vec::Vec};
 
+#[cfg(feature = "pui-core")]
+use core::ops::RangeInclusive;
 #[cfg(feature = "pui-core")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pui")))]
 use pui_core::OneShotIdentifier;
 
+#[cfg(feature = "pui-core")]
+mod pui_slice;
 mod pui_vec_index;
 
+#[cfg(feature = "pui-core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pui")))]
+pub use pui_slice::PuiSlice;
 pub use pui_vec_index::{BuildPuiVecIndex, PuiVecAccess, PuiVecIndex};
 
 /// A branded index that can be used to elide bounds checks
@@ -36,12 +43,32 @@ pub struct Id<T> {
 
 /// An append only `Vec` whitch returns branded indicies that
 /// can be used to elide bounds checks.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PuiVec<T, I> {
     ident: I,
     vec: Vec<T>,
 }
 
+/// Cloning a `PuiVec` requires the identifier to be `Clone`
+///
+/// An `Identifier` can't soundly be `Clone`, because both
+/// copies would own the same tokens. So this is only usable with unbranded
+/// `PuiVec`s, like `PuiVec<T, ()>`. To clone a branded `PuiVec`, use
+/// [`PuiVec::clone_with_ident`] with a fresh identifier.
+impl<T: Clone, I: Clone> Clone for PuiVec<T, I> {
+    fn clone(&self) -> Self {
+        Self {
+            ident: self.ident.clone(),
+            vec: self.vec.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.ident.clone_from(&source.ident);
+        self.vec.clone_from(&source.vec);
+    }
+}
+
 impl<T, I> From<PuiVec<T, I>> for Vec<T> {
     fn from(pui_vec: PuiVec<T, I>) -> Self { pui_vec.vec }
 }
@@ -103,6 +130,19 @@ impl<T, I> PuiVec<T, I> {
     /// minimal. Prefer reserve if future insertions are expected.
     pub fn reserve_exact(&mut self, additional: usize) { self.vec.reserve_exact(additional) }
 
+    /// Tries to reserve capacity for at least additional more elements to be inserted
+    /// in the given collection, see [`Vec::try_reserve`] for details.
+    ///
+    /// If this returns an error, the `PuiVec` is unchanged.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional)
+    }
+
+    /// Shrinks the capacity of the vector as much as possible.
+    ///
+    /// This doesn't change the length, so all ids stay valid
+    pub fn shrink_to_fit(&mut self) { self.vec.shrink_to_fit() }
+
     /// Returns a reference to an element or subslice depending on the type of index.
     ///
     /// * If given a position, returns a reference to the element at that position or None if out of bounds.
@@ -115,6 +155,57 @@ impl<T, I> PuiVec<T, I> {
     /// See [`get`](PuiVec::get) for details
     pub fn get_mut<A: PuiVecAccess<T, I>>(&mut self, index: A) -> Option<&mut A::Output> { index.get_mut(self) }
 
+    /// Returns shared references to many elements or subslices at once.
+    ///
+    /// Returns `None` if any of the indices are out of bounds.
+    /// See [`get`](PuiVec::get) for details
+    ///
+    /// Arrays of indices don't implement [`PuiVecAccess`], because it's methods return
+    /// a reference to the output, and there is nowhere to put an array of references
+    /// for it to point to.
+    pub fn get_many<A: PuiVecAccess<T, I>, const N: usize>(&self, indices: [A; N]) -> Option<[&A::Output; N]> {
+        if indices.iter().all(|index| index.contained_in(self)) {
+            Some(indices.map(|index| unsafe { index.get_unchecked(self) }))
+        } else {
+            None
+        }
+    }
+
+    /// Returns unique references to many elements at once.
+    ///
+    /// Returns `None` if any of the indices are out of bounds,
+    /// or if any two indices refer to the same element.
+    pub fn get_many_mut<A, const N: usize>(&mut self, indices: [A; N]) -> Option<[&mut T; N]>
+    where
+        A: PuiVecAccess<T, I, Output = T> + PuiVecIndex<I, SliceIndex = usize>,
+    {
+        for (i, index) in indices.iter().enumerate() {
+            if !index.contained_in(self) {
+                return None
+            }
+
+            let slice_index = index.slice_index();
+
+            if indices[..i].iter().any(|other| other.slice_index() == slice_index) {
+                return None
+            }
+        }
+
+        let ptr = self.vec.as_mut_ptr();
+        Some(indices.map(|index| unsafe { &mut *ptr.add(index.slice_index()) }))
+    }
+
+    /// Clones the elements of this `PuiVec` into a new `PuiVec` with the given identifier
+    ///
+    /// Any `Id`s created by this `PuiVec` can't be used with the clone, so
+    /// `ident` should be a fresh identifier
+    pub fn clone_with_ident<J>(&self, ident: J) -> PuiVec<T, J>
+    where
+        T: Clone,
+    {
+        PuiVec::from_raw_parts(self.vec.clone(), ident)
+    }
+
     /// Returns a reference to the identifier and a mutable reference to the underlying slice
     pub fn as_mut_parts(&mut self) -> (&I, &mut [T]) { (&self.ident, &mut self.vec) }
 
@@ -126,11 +217,61 @@ impl<T, I> PuiVec<T, I> {
     pub unsafe fn into_raw_parts(self) -> (I, Vec<T>) { (self.ident, self.vec) }
 }
 
+/// Operations that can shrink the `PuiVec` or move elements to different indices
+///
+/// These are only available without an identifier, because a `PuiVec` with an
+/// identifier must keep all of it's `Id`s in bounds and pointing to the same element.
+/// Without an identifier, indices are plain `usize`s that are always checked.
 // This is safe because `(): !Identifier`, so you can't create a corrosponding `Id`.
 // Which means there are is no safe unchecked accesses to the `Vec`
 impl<T> PuiVec<T, ()> {
     /// Get a mutable reference to the underling `Vec`
     pub fn vec_mut(&mut self) -> &mut Vec<T> { &mut self.vec }
+
+    /// Clears the `PuiVec`, removing all values.
+    pub fn clear(&mut self) { self.vec.clear() }
+
+    /// Removes the last element from the `PuiVec` and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> { self.vec.pop() }
+
+    /// Shortens the `PuiVec`, keeping the first `len` elements and dropping the rest.
+    ///
+    /// If `len` is greater than the `PuiVec`'s current length, this has no effect.
+    pub fn truncate(&mut self, len: usize) { self.vec.truncate(len) }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) { self.vec.retain(f) }
+
+    /// Removes consecutive repeated elements in the `PuiVec`
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.vec.dedup()
+    }
+
+    /// Removes all but the first of consecutive elements in the `PuiVec`
+    /// that resolve to the same key.
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, key: F) { self.vec.dedup_by_key(key) }
+
+    /// Removes all but the first of consecutive elements in the `PuiVec`
+    /// satisfying a given equality relation.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, same_bucket: F) { self.vec.dedup_by(same_bucket) }
+
+    /// Inserts an element at position `index` within the `PuiVec`, shifting all elements after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) { self.vec.insert(index, value) }
+
+    /// Removes and returns the element at position `index` within the `PuiVec`,
+    /// shifting all elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T { self.vec.remove(index) }
 }
 
 impl<T, I> PuiVec<T, I> {
@@ -178,6 +319,64 @@ impl<T, I: OneShotIdentifier> PuiVec<T, I> {
         })
     }
 
+    /// Returns an iterator over all the ids in the `PuiVec`, paired with
+    /// a unique reference to the corrosponding element
+    pub fn iter_mut_ids(&mut self) -> impl ExactSizeIterator<Item = (Id<I::Token>, &mut T)> {
+        let token = self.ident.token();
+        self.vec.iter_mut().enumerate().map(move |(index, value)| {
+            let id = Id {
+                index,
+                token: token.clone(),
+            };
+            (id, value)
+        })
+    }
+
+    /// Returns an iterator over chunks of `size` elements, paired with
+    /// the range of ids of each chunk
+    ///
+    /// The last chunk may be shorter than `size`, see [`slice::chunks`] for details
+    ///
+    /// Panics if `size` is zero
+    pub fn chunks_ids(&self, size: usize) -> impl ExactSizeIterator<Item = (RangeInclusive<Id<I::Token>>, &[T])> {
+        let token = self.ident.token();
+        self.vec.chunks(size).enumerate().map(move |(i, chunk)| {
+            let start = i * size;
+            let ids = Id {
+                index: start,
+                token: token.clone(),
+            }..=Id {
+                index: start + chunk.len() - 1,
+                token: token.clone(),
+            };
+            (ids, chunk)
+        })
+    }
+
+    /// Returns an iterator over chunks of `size` elements, paired with
+    /// the range of ids of each chunk
+    ///
+    /// The last chunk may be shorter than `size`, see [`slice::chunks_mut`] for details
+    ///
+    /// Panics if `size` is zero
+    pub fn chunks_mut_ids(
+        &mut self,
+        size: usize,
+    ) -> impl ExactSizeIterator<Item = (RangeInclusive<Id<I::Token>>, &mut [T])> {
+        let token = self.ident.token();
+        self.vec.chunks_mut(size).enumerate().map(move |(i, chunk)| {
+            let start = i * size;
+            let ids = Id {
+                index: start,
+                token: token.clone(),
+            }..=Id {
+                index: start + chunk.len() - 1,
+                token: token.clone(),
+            };
+            (ids, chunk)
+        })
+    }
+
     /// check if the `index` is in bounds, and if it is,
     /// return the corrosponding `Id`
     pub fn parse_id(&self, index: usize) -> Option<Id<I::Token>> {
@@ -191,6 +390,37 @@ impl<T, I: OneShotIdentifier> PuiVec<T, I> {
         }
     }
 
+    /// Binary searches this sorted `PuiVec` for the given element,
+    /// and returns the `Id` of the matching element if one was found
+    ///
+    /// see [`slice::binary_search`] for details
+    pub fn binary_search_id(&self, x: &T) -> Result<Id<I::Token>, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_id_by(|y| y.cmp(x))
+    }
+
+    /// Binary searches this sorted `PuiVec` with a comparator function,
+    /// and returns the `Id` of the matching element if one was found
+    ///
+    /// see [`slice::binary_search_by`] for details
+    pub fn binary_search_id_by<F: FnMut(&T) -> core::cmp::Ordering>(&self, f: F) -> Result<Id<I::Token>, usize> {
+        let index = self.vec.binary_search_by(f)?;
+        Ok(Id {
+            index,
+            token: self.ident.token(),
+        })
+    }
+
+    /// Binary searches this sorted `PuiVec` with a key extraction function,
+    /// and returns the `Id` of the matching element if one was found
+    ///
+    /// see [`slice::binary_search_by_key`] for details
+    pub fn binary_search_id_by_key<B: Ord, F: FnMut(&T) -> B>(&self, b: &B, mut f: F) -> Result<Id<I::Token>, usize> {
+        self.binary_search_id_by(|x| f(x).cmp(b))
+    }
+
     /// swap two elements, while eliding bounds checks
     pub fn swap(&mut self, a: Id<I::Token>, b: Id<I::Token>) {
         assert!(self.ident.owns_token(&a.token) && self.ident.owns_token(&b.token));
@@ -0,0 +1,180 @@
+use core::ops::{Deref, Index, Range};
+
+use crate::{Id, PuiVec};
+
+use pui_core::OneShotIdentifier;
+
+#[cold]
+#[inline(never)]
+fn not_owned() -> ! { panic!("Tried to use an id that isn't owned by the `PuiVec`") }
+
+#[cold]
+#[inline(never)]
+fn out_of_range() -> ! { panic!("Tried to use an id that is outside of the `PuiSlice`") }
+
+/// A checked view of a range of a [`PuiVec`], that can be indexed
+/// with the [`Id`]s of that `PuiVec`
+///
+/// Created by [`PuiVec::sub`] and [`PuiVec::as_pui_slice`]. Unlike indexing the `PuiVec`,
+/// this doesn't elide bounds checks. Ids are only guaranteed to be in bounds of the whole
+/// `PuiVec`, not the view, so every access checks the `Id` against the range of the view,
+/// just like indexing a slice.
+///
+/// ```rust
+/// # use pui_core::dynamic::Dynamic;
+/// # use pui_vec::{Id, PuiSlice, PuiVec};
+/// fn sum<T: Clone + Eq>(slice: PuiSlice<'_, u32, T>) -> u32 {
+///     if slice.len() <= 2 {
+///         return slice.iter().sum()
+///     }
+///
+///     let mid = slice.ids().nth(slice.len() / 2).unwrap();
+///     let (left, right) = slice.split_at(mid);
+///     sum(left) + sum(right)
+/// }
+///
+/// let mut vec = PuiVec::new(Dynamic::create());
+/// vec.extend(1..=10);
+/// let five: Id<_> = vec.parse_id(4).unwrap();
+///
+/// assert_eq!(sum(vec.as_pui_slice()), 55);
+/// assert_eq!(vec.as_pui_slice().split_at(five).1[five], 5);
+/// ```
+#[derive(Debug)]
+pub struct PuiSlice<'a, T, Tok> {
+    start: usize,
+    slice: &'a [T],
+    token: Tok,
+}
+
+impl<T, Tok: Clone> Clone for PuiSlice<'_, T, Tok> {
+    fn clone(&self) -> Self {
+        Self {
+            start: self.start,
+            slice: self.slice,
+            token: self.token.clone(),
+        }
+    }
+}
+
+impl<T, I: OneShotIdentifier> PuiVec<T, I> {
+    /// Get a checked view of the whole `PuiVec`
+    pub fn as_pui_slice(&self) -> PuiSlice<'_, T, I::Token> {
+        PuiSlice {
+            start: 0,
+            slice: &self.vec,
+            token: self.ident.token(),
+        }
+    }
+
+    /// Get a checked view of the `range` of the `PuiVec`, which can be indexed by the
+    /// ids of this `PuiVec`, see [`PuiSlice`] for details
+    pub fn sub(&self, range: Range<Id<I::Token>>) -> PuiSlice<'_, T, I::Token> { self.as_pui_slice().sub(range) }
+}
+
+impl<'a, T, Tok: Eq + Clone> PuiSlice<'a, T, Tok> {
+    fn check_owned(&self, id: &Id<Tok>) {
+        if id.token != self.token {
+            not_owned()
+        }
+    }
+
+    /// Returns true if the `id` is in this view
+    ///
+    /// # Panics
+    ///
+    /// If `id` isn't owned by the `PuiVec` this view was created from
+    pub fn contains(&self, id: &Id<Tok>) -> bool {
+        self.check_owned(id);
+        id.index.wrapping_sub(self.start) < self.slice.len()
+    }
+
+    /// Returns a shared reference to the element at `id`, or `None` if it's outside of this view
+    ///
+    /// # Panics
+    ///
+    /// If `id` isn't owned by the `PuiVec` this view was created from
+    pub fn get(&self, id: Id<Tok>) -> Option<&'a T> {
+        if self.contains(&id) {
+            Some(unsafe { self.slice.get_unchecked(id.index - self.start) })
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over all the ids in this view
+    pub fn ids(&self) -> impl ExactSizeIterator<Item = Id<Tok>> + Clone {
+        let token = self.token.clone();
+        (self.start..self.start + self.slice.len()).map(move |index| Id {
+            index,
+            token: token.clone(),
+        })
+    }
+
+    /// Get a view of the `range` of this view
+    ///
+    /// # Panics
+    ///
+    /// If the ids in `range` aren't owned by the `PuiVec` this view was created from,
+    /// if the range is reversed, or if it isn't inside this view
+    pub fn sub(&self, range: Range<Id<Tok>>) -> Self {
+        self.check_owned(&range.start);
+        self.check_owned(&range.end);
+
+        let end = self.start + self.slice.len();
+        if range.start.index < self.start || range.end.index < range.start.index || end < range.end.index {
+            out_of_range()
+        }
+
+        Self {
+            start: range.start.index,
+            slice: &self.slice[range.start.index - self.start..range.end.index - self.start],
+            token: self.token.clone(),
+        }
+    }
+
+    /// Divides this view into two views at `mid`
+    ///
+    /// The first will contain all ids before `mid`, and the second
+    /// will contain `mid` and all ids after it
+    ///
+    /// # Panics
+    ///
+    /// If `mid` isn't owned by the `PuiVec` this view was created from or if it isn't inside this view
+    pub fn split_at(&self, mid: Id<Tok>) -> (Self, Self) {
+        if !self.contains(&mid) {
+            out_of_range()
+        }
+
+        let (left, right) = self.slice.split_at(mid.index - self.start);
+        (
+            Self {
+                start: self.start,
+                slice: left,
+                token: self.token.clone(),
+            },
+            Self {
+                start: mid.index,
+                slice: right,
+                token: self.token.clone(),
+            },
+        )
+    }
+}
+
+impl<T, Tok: Eq + Clone> Index<Id<Tok>> for PuiSlice<'_, T, Tok> {
+    type Output = T;
+
+    fn index(&self, id: Id<Tok>) -> &Self::Output {
+        match self.get(id) {
+            Some(value) => value,
+            None => out_of_range(),
+        }
+    }
+}
+
+impl<T, Tok> Deref for PuiSlice<'_, T, Tok> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target { self.slice }
+}
@@ -325,7 +325,8 @@ impl<I: OneShotIdentifier> PuiVecIndex<I> for Range<Id<I::Token>> {
 
     fn contained_in<T>(&self, vec: &PuiVec<T, I>) -> bool {
         if vec.ident.owns_token(&self.start.token) && vec.ident.owns_token(&self.end.token) {
-            true
+            // a reversed range is rejected like any other out of bounds range
+            self.start.index <= self.end.index
         } else {
             not_owned()
         }
@@ -371,7 +372,8 @@ impl<I: OneShotIdentifier> PuiVecIndex<I> for RangeInclusive<Id<I::Token>> {
 
     fn contained_in<T>(&self, vec: &PuiVec<T, I>) -> bool {
         if vec.ident.owns_token(&self.start().token) && vec.ident.owns_token(&self.end().token) {
-            true
+            // `a..=b` where `a == b + 1` is empty, anything further apart is reversed
+            self.start().index <= self.end().index + 1
         } else {
             not_owned()
         }
@@ -0,0 +1,34 @@
+use pui_core::dynamic::Dynamic;
+use pui_vec::PuiVec;
+
+#[test]
+fn chunks_ids() {
+    let mut vec = PuiVec::new(Dynamic::create());
+    vec.extend(0..10);
+
+    let chunks: Vec<_> = vec.chunks_ids(4).collect();
+    assert_eq!(chunks.len(), 3);
+
+    for (ids, chunk) in chunks {
+        assert_eq!(&vec[ids.clone()], chunk);
+        assert_eq!(ids.end().get() - ids.start().get() + 1, chunk.len());
+    }
+
+    let ids: Vec<_> = vec
+        .chunks_mut_ids(3)
+        .map(|(ids, chunk)| {
+            chunk.iter_mut().for_each(|x| *x *= 2);
+            ids
+        })
+        .collect();
+
+    assert_eq!(vec[ids[3].clone()], [18]);
+    assert_eq!(vec[ids[0].clone()], [0, 2, 4]);
+}
+
+#[test]
+#[should_panic]
+fn chunks_ids_zero() {
+    let vec = PuiVec::<i32, _>::new(Dynamic::create());
+    let _ = vec.chunks_ids(0);
+}
@@ -0,0 +1,79 @@
+use pui_core::dynamic::Dynamic;
+use pui_vec::{Id, PuiVec};
+
+type Token = <Dynamic as pui_core::Identifier>::Token;
+
+fn pair() -> (PuiVec<i32, Dynamic>, PuiVec<i32, Dynamic>, Id<Token>) {
+    let mut a = PuiVec::new(Dynamic::create());
+    let mut b = PuiVec::new(Dynamic::create());
+    for i in 0..4 {
+        b.push::<Id<_>>(i);
+    }
+    let id = a.push(10);
+    (a, b, id)
+}
+
+#[test]
+#[should_panic]
+fn get_foreign() {
+    let (_a, b, id) = pair();
+    b.get(id);
+}
+
+#[test]
+#[should_panic]
+fn get_mut_foreign() {
+    let (_a, mut b, id) = pair();
+    b.get_mut(id);
+}
+
+#[test]
+#[should_panic]
+fn index_foreign() {
+    let (_a, b, id) = pair();
+    let _ = b[id];
+}
+
+#[test]
+#[should_panic]
+fn range_foreign() {
+    let (a, b, id) = pair();
+    let end = a.ids().last().unwrap();
+    b.get(id..end);
+}
+
+#[test]
+#[should_panic]
+fn swap_foreign() {
+    let (_a, mut b, id) = pair();
+    let other = b.ids().next().unwrap();
+    b.swap(id, other);
+}
+
+#[test]
+#[should_panic]
+fn split_at_foreign() {
+    let (_a, b, id) = pair();
+    b.split_at(id);
+}
+
+#[test]
+fn reversed_range() {
+    let (_a, mut b, _) = pair();
+    let ids: Vec<Id<Token>> = b.ids().collect();
+    let [w, x, y, z] = [ids[0], ids[1], ids[2], ids[3]];
+
+    assert_eq!(b.get(w..z), Some(&[0, 1, 2][..]));
+    assert_eq!(b.get(z..w), None);
+    assert_eq!(b.get_mut(y..x), None);
+    assert_eq!(b.get(y..=x), Some(&[][..]));
+    assert_eq!(b.get(z..=x), None);
+}
+
+#[test]
+#[should_panic]
+fn index_reversed_range() {
+    let (_a, b, _) = pair();
+    let ids: Vec<Id<Token>> = b.ids().collect();
+    let _ = &b[ids[3]..ids[0]];
+}
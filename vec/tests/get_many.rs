@@ -0,0 +1,48 @@
+use pui_core::dynamic::Dynamic;
+use pui_vec::{Id, PuiVec};
+
+type Token = <Dynamic as pui_core::Identifier>::Token;
+
+fn setup() -> (PuiVec<i32, Dynamic>, [Id<Token>; 4]) {
+    let mut vec = PuiVec::new(Dynamic::create());
+    let ids = [vec.push(0), vec.push(1), vec.push(2), vec.push(3)];
+    (vec, ids)
+}
+
+#[test]
+fn get_many() {
+    let (vec, [a, b, c, _]) = setup();
+    assert_eq!(vec.get_many([c, a, b]), Some([&2, &0, &1]));
+    assert_eq!(vec.get_many([a, a]), Some([&0, &0]));
+    assert_eq!(vec.get_many([3, 4]), None);
+}
+
+#[test]
+fn get_many_mut() {
+    let (mut vec, [a, b, c, d]) = setup();
+    let [x, y, z] = vec.get_many_mut([d, a, c]).unwrap();
+    std::mem::swap(x, y);
+    *z += 10;
+    assert_eq!(vec.get_many([a, b]), Some([&3, &1]));
+    assert_eq!(*vec, [3, 1, 12, 0]);
+}
+
+#[test]
+fn get_many_mut_duplicate() {
+    let (mut vec, [a, b, c, _]) = setup();
+    assert_eq!(vec.get_many_mut([a, b, a]), None);
+    assert_eq!(vec.get_many_mut([b, c, c]), None);
+    assert_eq!(vec.get_many_mut([a]), Some([&mut 0]));
+    assert_eq!(vec.get_many_mut([2, 0, 2]), None);
+    assert_eq!(vec.get_many_mut([1, 1]), None);
+    assert_eq!(vec.get_many_mut([0, 4]), None);
+    assert_eq!(vec.get_many_mut::<usize, 0>([]), Some([]));
+}
+
+#[test]
+#[should_panic]
+fn get_many_mut_foreign() {
+    let (mut vec, _) = setup();
+    let (_, [id, ..]) = setup();
+    vec.get_many_mut([id]);
+}
@@ -0,0 +1,49 @@
+use pui_core::dynamic::Dynamic;
+use pui_vec::{Id, PuiVec};
+
+#[test]
+fn sub() {
+    let mut vec = PuiVec::new(Dynamic::create());
+    vec.extend(0..10);
+    let ids: Vec<Id<_>> = vec.ids().collect();
+
+    let sub = vec.sub(ids[2]..ids[7]);
+    assert_eq!(*sub, [2, 3, 4, 5, 6]);
+    assert_eq!(sub[ids[2]], 2);
+    assert_eq!(sub.get(ids[6]), Some(&6));
+    assert_eq!(sub.get(ids[7]), None);
+    assert_eq!(sub.get(ids[1]), None);
+    assert!(sub.ids().eq(ids[2..7].iter().copied()));
+
+    let inner = sub.sub(ids[3]..ids[5]);
+    assert_eq!(*inner, [3, 4]);
+    assert!(!inner.contains(&ids[5]));
+
+    let (left, right) = sub.split_at(ids[4]);
+    assert_eq!(*left, [2, 3]);
+    assert_eq!(*right, [4, 5, 6]);
+    assert_eq!(right[ids[6]], 6);
+}
+
+#[test]
+#[should_panic = "outside of the `PuiSlice`"]
+fn sub_outside() {
+    let mut vec = PuiVec::new(Dynamic::create());
+    vec.extend(0..10);
+    let ids: Vec<Id<_>> = vec.ids().collect();
+
+    let sub = vec.sub(ids[2]..ids[7]);
+    sub.sub(ids[1]..ids[3]);
+}
+
+#[test]
+#[should_panic = "isn't owned by the `PuiVec`"]
+fn foreign_id() {
+    let mut vec = PuiVec::new(Dynamic::create());
+    let mut other = PuiVec::new(Dynamic::create());
+    vec.extend(0..10);
+    other.extend(0..10);
+
+    let id: Id<_> = other.parse_id(3).unwrap();
+    vec.as_pui_slice().get(id);
+}